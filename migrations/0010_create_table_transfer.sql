@@ -0,0 +1,35 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "public"."transfer"(
+    "id" bigserial PRIMARY KEY,
+    "from_user_id" int NOT NULL,
+    "to_user_id" int NOT NULL,
+    "asset_type_id" int NOT NULL,
+    "amount" DECIMAL(18, 6) NOT NULL,
+    "order_number" text UNIQUE NOT NULL,
+    "description" text NOT NULL DEFAULT '',
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON COLUMN "public"."transfer"."id" IS '主键自增id';
+
+COMMENT ON COLUMN "public"."transfer"."from_user_id" IS '转出用户id';
+
+COMMENT ON COLUMN "public"."transfer"."to_user_id" IS '转入用户id';
+
+COMMENT ON COLUMN "public"."transfer"."asset_type_id" IS '资产类型id';
+
+COMMENT ON COLUMN "public"."transfer"."amount" IS '转账金额';
+
+COMMENT ON COLUMN "public"."transfer"."order_number" IS '订单号';
+
+COMMENT ON COLUMN "public"."transfer"."description" IS '转账描述';
+
+COMMENT ON COLUMN "public"."transfer"."created_at" IS '创建时间';
+
+COMMENT ON TABLE "public"."transfer" IS '用户转账表';
+
+-- 转出、转入两条账户日志通过`transfer_id`关联
+ALTER TABLE "public"."account_log"
+    ADD COLUMN IF NOT EXISTS "transfer_id" bigint;
+
+COMMENT ON COLUMN "public"."account_log"."transfer_id" IS '转账id';
@@ -0,0 +1,58 @@
+-- Add migration script here
+CREATE TYPE hold_status_enum AS ENUM(
+    'ACTIVE',
+    'CAPTURED',
+    'VOIDED',
+    'EXPIRED'
+);
+
+CREATE TABLE IF NOT EXISTS "public"."hold"(
+    "id" bigserial PRIMARY KEY,
+    "user_id" int NOT NULL,
+    "asset_type_id" int NOT NULL,
+    "amount" DECIMAL(18, 6) NOT NULL,
+    "captured_amount" DECIMAL(18, 6) NOT NULL DEFAULT 0,
+    "released_amount" DECIMAL(18, 6) NOT NULL DEFAULT 0,
+    "status" hold_status_enum NOT NULL DEFAULT 'ACTIVE',
+    "order_number" text UNIQUE NOT NULL,
+    "description" text NOT NULL DEFAULT '',
+    "expires_at" timestamptz NOT NULL,
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX hold_expires_at_idx ON "public"."hold"("expires_at")
+WHERE
+    "status" = 'ACTIVE';
+
+COMMENT ON COLUMN "public"."hold"."id" IS '主键自增id';
+
+COMMENT ON COLUMN "public"."hold"."user_id" IS '用户id';
+
+COMMENT ON COLUMN "public"."hold"."asset_type_id" IS '资产类型id';
+
+COMMENT ON COLUMN "public"."hold"."amount" IS '预授权金额';
+
+COMMENT ON COLUMN "public"."hold"."captured_amount" IS '已扣款金额';
+
+COMMENT ON COLUMN "public"."hold"."released_amount" IS '已释放金额';
+
+COMMENT ON COLUMN "public"."hold"."status" IS '预授权状态';
+
+COMMENT ON COLUMN "public"."hold"."order_number" IS '订单号';
+
+COMMENT ON COLUMN "public"."hold"."description" IS '预授权描述';
+
+COMMENT ON COLUMN "public"."hold"."expires_at" IS '过期时间';
+
+COMMENT ON COLUMN "public"."hold"."created_at" IS '创建时间';
+
+COMMENT ON COLUMN "public"."hold"."updated_at" IS '更新时间';
+
+COMMENT ON TABLE "public"."hold" IS '预授权表';
+
+CREATE TRIGGER update_hold_timestamp
+    BEFORE UPDATE ON "public"."hold"
+    FOR EACH ROW
+    WHEN(NEW IS DISTINCT FROM OLD)
+    EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "public"."idempotency_key"(
+    "client_id" text NOT NULL DEFAULT '',
+    "order_number" text NOT NULL,
+    "request_hash" text NOT NULL,
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("client_id", "order_number")
+);
+
+COMMENT ON COLUMN "public"."idempotency_key"."client_id" IS '客户端标识';
+
+COMMENT ON COLUMN "public"."idempotency_key"."order_number" IS '订单号';
+
+COMMENT ON COLUMN "public"."idempotency_key"."request_hash" IS '请求摘要';
+
+COMMENT ON COLUMN "public"."idempotency_key"."created_at" IS '创建时间';
+
+COMMENT ON TABLE "public"."idempotency_key" IS '账户操作幂等键表';
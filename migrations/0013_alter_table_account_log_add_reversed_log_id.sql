@@ -0,0 +1,8 @@
+-- Add migration script here
+-- 冲正日志通过`reversed_log_id`关联被冲正的原日志
+ALTER TABLE "public"."account_log"
+    ADD COLUMN IF NOT EXISTS "reversed_log_id" bigint;
+
+COMMENT ON COLUMN "public"."account_log"."reversed_log_id" IS '被冲正的日志id';
+
+CREATE INDEX account_log_order_number_idx ON "public"."account_log"("account_id", "order_number");
@@ -0,0 +1,24 @@
+-- Add migration script here
+-- 数据变更后通过`config_change`通道通知各服务实例刷新缓存，通知内容为表名
+-- 通知在事务提交后才会送达
+CREATE OR REPLACE FUNCTION track_change()
+    RETURNS TRIGGER
+    AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO change_log(TABLE_NAME, operation_type, new_data)
+            VALUES(TG_TABLE_NAME, 'INSERT', ROW_TO_JSON(NEW)::jsonb - 'created_at' - 'updated_at');
+    ELSIF TG_OP = 'UPDATE'
+            AND NEW IS DISTINCT FROM OLD THEN
+            INSERT INTO change_log(TABLE_NAME, operation_type, old_data, new_data)
+                VALUES(TG_TABLE_NAME, 'UPDATE', ROW_TO_JSON(OLD)::jsonb - 'created_at' - 'updated_at', ROW_TO_JSON(NEW)::jsonb - 'created_at' - 'updated_at');
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO change_log(TABLE_NAME, operation_type, old_data)
+            VALUES(TG_TABLE_NAME, 'DELETE', ROW_TO_JSON(OLD)::jsonb - 'created_at' - 'updated_at');
+    END IF;
+    PERFORM
+        pg_notify('config_change', TG_TABLE_NAME);
+    RETURN NEW;
+END;
+$$
+LANGUAGE plpgsql;
@@ -0,0 +1,46 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "public"."client"(
+    "id" serial PRIMARY KEY,
+    "name" text UNIQUE NOT NULL,
+    "api_key_hash" text UNIQUE NOT NULL,
+    "asset_type_ids" int[] NOT NULL DEFAULT '{}',
+    "action_type_ids" int[] NOT NULL DEFAULT '{}',
+    "is_active" boolean NOT NULL DEFAULT FALSE,
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON COLUMN "public"."client"."id" IS '主键自增id';
+
+COMMENT ON COLUMN "public"."client"."name" IS '客户端名称';
+
+COMMENT ON COLUMN "public"."client"."api_key_hash" IS 'API密钥SHA-256摘要';
+
+COMMENT ON COLUMN "public"."client"."asset_type_ids" IS '允许使用的资产类型id';
+
+COMMENT ON COLUMN "public"."client"."action_type_ids" IS '允许使用的操作类型id';
+
+COMMENT ON COLUMN "public"."client"."is_active" IS '是否启用';
+
+COMMENT ON COLUMN "public"."client"."created_at" IS '创建时间';
+
+COMMENT ON COLUMN "public"."client"."updated_at" IS '更新时间';
+
+COMMENT ON TABLE "public"."client" IS '接入客户端表';
+
+CREATE TRIGGER update_client_timestamp
+    BEFORE UPDATE ON "public"."client"
+    FOR EACH ROW
+    WHEN(NEW IS DISTINCT FROM OLD)
+    EXECUTE FUNCTION update_timestamp();
+
+CREATE TRIGGER track_client_change
+    AFTER INSERT OR UPDATE OR DELETE ON "public"."client"
+    FOR EACH ROW
+    EXECUTE FUNCTION track_change();
+
+-- 记录发起账户操作的客户端，系统自动处理（如预授权过期释放）时为空
+ALTER TABLE "public"."account_log"
+    ADD COLUMN IF NOT EXISTS "client_id" int;
+
+COMMENT ON COLUMN "public"."account_log"."client_id" IS '客户端id';
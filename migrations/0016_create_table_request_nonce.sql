@@ -0,0 +1,23 @@
+-- Add migration script here
+-- 启用请求签名的客户端需保存签名密钥原文用于校验，为空表示不校验签名
+ALTER TABLE "public"."client"
+    ADD COLUMN IF NOT EXISTS "signing_secret" text;
+
+COMMENT ON COLUMN "public"."client"."signing_secret" IS '请求签名密钥';
+
+CREATE TABLE IF NOT EXISTS "public"."request_nonce"(
+    "client_id" int NOT NULL,
+    "nonce" text NOT NULL,
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("client_id", "nonce")
+);
+
+CREATE INDEX IF NOT EXISTS request_nonce_created_at_idx ON "public"."request_nonce"("created_at");
+
+COMMENT ON COLUMN "public"."request_nonce"."client_id" IS '客户端id';
+
+COMMENT ON COLUMN "public"."request_nonce"."nonce" IS '请求随机数';
+
+COMMENT ON COLUMN "public"."request_nonce"."created_at" IS '创建时间';
+
+COMMENT ON TABLE "public"."request_nonce" IS '已使用的请求随机数表';
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- 游标分页按`(created_at, id)`倒序定位，不按操作类型过滤时使用
+CREATE INDEX IF NOT EXISTS account_log_cursor_idx ON "public"."account_log"("account_id", "created_at" DESC, "id" DESC);
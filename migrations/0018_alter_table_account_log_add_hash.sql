@@ -0,0 +1,13 @@
+-- Add migration script here
+-- 按账户串联的哈希链，每条记录的哈希覆盖记录内容及同账户上一条记录的哈希
+-- 迁移前的历史记录哈希为空，哈希链从迁移后的第一条记录开始
+ALTER TABLE "public"."account_log"
+    ADD COLUMN IF NOT EXISTS "prev_hash" text,
+    ADD COLUMN IF NOT EXISTS "hash" text;
+
+COMMENT ON COLUMN "public"."account_log"."prev_hash" IS '同账户上一条记录的哈希';
+
+COMMENT ON COLUMN "public"."account_log"."hash" IS '记录哈希';
+
+-- 哈希链按id顺序串联，同账户写入由账户行锁串行化，id顺序即写入顺序
+CREATE INDEX IF NOT EXISTS account_log_chain_idx ON "public"."account_log"("account_id", "id");
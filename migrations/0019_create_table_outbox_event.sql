@@ -0,0 +1,24 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "public"."outbox_event"(
+    "id" bigserial PRIMARY KEY,
+    "event_type" text NOT NULL,
+    "payload" jsonb NOT NULL,
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "published_at" timestamptz
+);
+
+CREATE INDEX IF NOT EXISTS outbox_event_unpublished_idx ON "public"."outbox_event"("id")
+WHERE
+    "published_at" IS NULL;
+
+COMMENT ON COLUMN "public"."outbox_event"."id" IS '主键自增id，即发布顺序';
+
+COMMENT ON COLUMN "public"."outbox_event"."event_type" IS '事件类型';
+
+COMMENT ON COLUMN "public"."outbox_event"."payload" IS '事件内容';
+
+COMMENT ON COLUMN "public"."outbox_event"."created_at" IS '创建时间';
+
+COMMENT ON COLUMN "public"."outbox_event"."published_at" IS '发布时间，未发布时为空';
+
+COMMENT ON TABLE "public"."outbox_event" IS '余额变更事件发件箱表';
@@ -0,0 +1,85 @@
+-- Add migration script here
+CREATE TYPE webhook_delivery_status_enum AS ENUM(
+    'PENDING',
+    'SUCCEEDED',
+    'DEAD'
+);
+
+CREATE TABLE IF NOT EXISTS "public"."webhook_subscription"(
+    "id" serial PRIMARY KEY,
+    "client_id" int NOT NULL REFERENCES "public"."client"("id"),
+    "url" text NOT NULL,
+    "secret" text NOT NULL,
+    "asset_type_ids" int[] NOT NULL DEFAULT '{}',
+    "action_type_ids" int[] NOT NULL DEFAULT '{}',
+    "is_active" boolean NOT NULL DEFAULT TRUE,
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON COLUMN "public"."webhook_subscription"."id" IS '主键自增id';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."client_id" IS '客户端id';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."url" IS '回调地址';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."secret" IS '回调签名密钥';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."asset_type_ids" IS '订阅的资产类型id，为空时订阅客户端允许使用的全部资产类型';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."action_type_ids" IS '订阅的操作类型id，为空时订阅全部操作类型';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."is_active" IS '是否启用';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."created_at" IS '创建时间';
+
+COMMENT ON COLUMN "public"."webhook_subscription"."updated_at" IS '更新时间';
+
+COMMENT ON TABLE "public"."webhook_subscription" IS '回调订阅表';
+
+CREATE TRIGGER update_webhook_subscription_timestamp
+    BEFORE UPDATE ON "public"."webhook_subscription"
+    FOR EACH ROW
+    WHEN(NEW IS DISTINCT FROM OLD)
+    EXECUTE FUNCTION update_timestamp();
+
+CREATE TABLE IF NOT EXISTS "public"."webhook_delivery"(
+    "id" bigserial PRIMARY KEY,
+    "subscription_id" int NOT NULL REFERENCES "public"."webhook_subscription"("id"),
+    "event_type" text NOT NULL,
+    "payload" jsonb NOT NULL,
+    "status" webhook_delivery_status_enum NOT NULL DEFAULT 'PENDING',
+    "attempts" int NOT NULL DEFAULT 0,
+    "next_attempt_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "last_error" text NOT NULL DEFAULT '',
+    "created_at" timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "delivered_at" timestamptz
+);
+
+CREATE INDEX IF NOT EXISTS webhook_delivery_pending_idx ON "public"."webhook_delivery"("next_attempt_at")
+WHERE
+    "status" = 'PENDING';
+
+CREATE INDEX IF NOT EXISTS webhook_delivery_subscription_idx ON "public"."webhook_delivery"("subscription_id", "id");
+
+COMMENT ON COLUMN "public"."webhook_delivery"."id" IS '主键自增id';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."subscription_id" IS '回调订阅id';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."event_type" IS '事件类型';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."payload" IS '回调内容';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."status" IS '投递状态';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."attempts" IS '已尝试次数';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."next_attempt_at" IS '下次尝试时间';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."last_error" IS '最近一次失败原因';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."created_at" IS '创建时间';
+
+COMMENT ON COLUMN "public"."webhook_delivery"."delivered_at" IS '投递成功时间';
+
+COMMENT ON TABLE "public"."webhook_delivery" IS '回调投递表';
@@ -0,0 +1,20 @@
+-- Add migration script here
+CREATE SEQUENCE IF NOT EXISTS "public"."account_action_batch_id_seq";
+
+COMMENT ON SEQUENCE "public"."account_action_batch_id_seq" IS '批量账户操作批次id';
+
+-- 同一次`/accounts/actions`请求写入的记录共用一个批次id，其他操作为空
+ALTER TABLE "public"."account_log"
+    ADD COLUMN IF NOT EXISTS "batch_id" bigint;
+
+COMMENT ON COLUMN "public"."account_log"."batch_id" IS '批次id';
+
+CREATE INDEX IF NOT EXISTS account_log_batch_id_idx ON "public"."account_log"("batch_id")
+WHERE
+    "batch_id" IS NOT NULL;
+
+-- 记录幂等键对应的批次，同一请求重试时按批次返回首次处理结果
+ALTER TABLE "public"."idempotency_key"
+    ADD COLUMN IF NOT EXISTS "batch_id" bigint;
+
+COMMENT ON COLUMN "public"."idempotency_key"."batch_id" IS '批次id';
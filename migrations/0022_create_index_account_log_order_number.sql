@@ -0,0 +1,3 @@
+-- Add migration script here
+-- 按订单号跨账户查询操作记录
+CREATE INDEX IF NOT EXISTS account_log_order_lookup_idx ON "public"."account_log"("order_number");
@@ -0,0 +1,6 @@
+-- Add migration script here
+-- 预授权只能由发起的客户端扣款、撤销及查询
+ALTER TABLE "public"."hold"
+    ADD COLUMN IF NOT EXISTS "client_id" int;
+
+COMMENT ON COLUMN "public"."hold"."client_id" IS '发起预授权的客户端id';
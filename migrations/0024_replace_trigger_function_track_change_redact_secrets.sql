@@ -0,0 +1,35 @@
+-- Add migration script here
+-- 变更日志不记录密钥类字段（`client`表的API密钥摘要及签名密钥）
+CREATE OR REPLACE FUNCTION track_change()
+    RETURNS TRIGGER
+    AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO change_log(TABLE_NAME, operation_type, new_data)
+            VALUES(TG_TABLE_NAME, 'INSERT', ROW_TO_JSON(NEW)::jsonb - 'created_at' - 'updated_at' - 'api_key_hash' - 'signing_secret');
+    ELSIF TG_OP = 'UPDATE'
+            AND NEW IS DISTINCT FROM OLD THEN
+            INSERT INTO change_log(TABLE_NAME, operation_type, old_data, new_data)
+                VALUES(TG_TABLE_NAME, 'UPDATE', ROW_TO_JSON(OLD)::jsonb - 'created_at' - 'updated_at' - 'api_key_hash' - 'signing_secret', ROW_TO_JSON(NEW)::jsonb - 'created_at' - 'updated_at' - 'api_key_hash' - 'signing_secret');
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO change_log(TABLE_NAME, operation_type, old_data)
+            VALUES(TG_TABLE_NAME, 'DELETE', ROW_TO_JSON(OLD)::jsonb - 'created_at' - 'updated_at' - 'api_key_hash' - 'signing_secret');
+    END IF;
+    PERFORM
+        pg_notify('config_change', TG_TABLE_NAME);
+    RETURN NEW;
+END;
+$$
+LANGUAGE plpgsql;
+
+-- 清除已记录的密钥
+UPDATE
+    "public"."change_log"
+SET
+    old_data = old_data - 'api_key_hash' - 'signing_secret',
+    new_data = new_data - 'api_key_hash' - 'signing_secret'
+WHERE
+    "table_name" = 'client';
+
+-- 签名密钥由服务使用`STARDUST_SECRET_KEY`加密后保存，已有的明文密钥在服务启动时加密
+COMMENT ON COLUMN "public"."client"."signing_secret" IS '请求签名密钥（AES-256-GCM加密）';
@@ -0,0 +1,25 @@
+-- Add migration script here
+-- 在账户行锚定哈希链的首尾，与余额在同一行锁下更新，用于发现删除最新或最早的记录
+ALTER TABLE "public"."account"
+    ADD COLUMN IF NOT EXISTS "chain_start_log_id" bigint,
+    ADD COLUMN IF NOT EXISTS "last_log_hash" text;
+
+COMMENT ON COLUMN "public"."account"."chain_start_log_id" IS '哈希链第一条在线记录的id';
+
+COMMENT ON COLUMN "public"."account"."last_log_hash" IS '哈希链最后一条记录的哈希';
+
+-- 以现有记录回填锚点
+UPDATE "public"."account" a
+SET
+    "chain_start_log_id" = (
+        SELECT MIN(l."id")
+        FROM "public"."account_log" l
+        WHERE l."account_id" = a."id" AND l."hash" IS NOT NULL
+    ),
+    "last_log_hash" = (
+        SELECT l."hash"
+        FROM "public"."account_log" l
+        WHERE l."account_id" = a."id"
+        ORDER BY l."id" DESC
+        LIMIT 1
+    );
@@ -0,0 +1,20 @@
+-- Add migration script here
+-- 转账专用操作类型，只变更可用余额，不计入累计收入和累计支出
+INSERT INTO "public"."action_type"("name", "description", "available_balance_change", "frozen_balance_change", "total_income_change", "total_expense_change", "is_active")
+    VALUES ('TRF_OUT', '转账转出(可用余额减少)', 'DEC', 'NONE', 'NONE', 'NONE', 't'),
+('TRF_IN', '转账转入(可用余额增加)', 'INC', 'NONE', 'NONE', 'NONE', 't')
+ON CONFLICT ("name")
+    DO NOTHING;
+
+-- 原可使用`AB_EXP`/`AB_INC`转账的客户端继续允许转账
+UPDATE "public"."client"
+SET "action_type_ids" = "action_type_ids" || ARRAY(
+    SELECT "id" FROM "public"."action_type" WHERE "name" = 'TRF_OUT')
+WHERE (SELECT "id" FROM "public"."action_type" WHERE "name" = 'AB_EXP') = ANY ("action_type_ids")
+    AND NOT (SELECT "id" FROM "public"."action_type" WHERE "name" = 'TRF_OUT') = ANY ("action_type_ids");
+
+UPDATE "public"."client"
+SET "action_type_ids" = "action_type_ids" || ARRAY(
+    SELECT "id" FROM "public"."action_type" WHERE "name" = 'TRF_IN')
+WHERE (SELECT "id" FROM "public"."action_type" WHERE "name" = 'AB_INC') = ANY ("action_type_ids")
+    AND NOT (SELECT "id" FROM "public"."action_type" WHERE "name" = 'TRF_IN') = ANY ("action_type_ids");
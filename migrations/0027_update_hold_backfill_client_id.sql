@@ -0,0 +1,16 @@
+-- Add migration script here
+-- 以冻结记录的客户端回填`hold.client_id`，无法确定客户端的预授权只能等待过期自动释放
+UPDATE "public"."hold" h
+SET "client_id" = (
+    SELECT l."client_id"
+    FROM "public"."account_log" l
+        JOIN "public"."account" a ON a."id" = l."account_id"
+        JOIN "public"."action_type" t ON t."id" = l."action_type_id"
+    WHERE a."user_id" = h."user_id"
+        AND a."asset_type_id" = h."asset_type_id"
+        AND l."order_number" = h."order_number"
+        AND t."name" = 'FRZ'
+        AND l."client_id" IS NOT NULL
+    ORDER BY l."id"
+    LIMIT 1)
+WHERE h."client_id" IS NULL;
@@ -0,0 +1,14 @@
+-- Add migration script here
+-- 客户端id改为整数并关联`client`表
+-- 引入客户端前写入的幂等键（客户端标识为空）及已删除客户端的幂等键不会再被使用，直接删除
+DELETE FROM "public"."idempotency_key"
+WHERE "client_id" !~ '^[0-9]+$'
+    OR NOT EXISTS (
+        SELECT 1 FROM "public"."client" WHERE "client"."id"::text = "idempotency_key"."client_id");
+
+ALTER TABLE "public"."idempotency_key"
+    ALTER COLUMN "client_id" DROP DEFAULT,
+    ALTER COLUMN "client_id" TYPE int USING "client_id"::int,
+    ADD CONSTRAINT idempotency_key_client_id_fkey FOREIGN KEY ("client_id") REFERENCES "public"."client"("id");
+
+COMMENT ON COLUMN "public"."idempotency_key"."client_id" IS '客户端id';
@@ -0,0 +1,7 @@
+-- Add migration script here
+-- 哈希格式版本：版本1为原有格式，版本2起`batch_id`参与哈希计算
+-- 已有记录及恢复的归档记录默认为版本1，新记录由应用写入当前版本
+ALTER TABLE "public"."account_log"
+    ADD COLUMN IF NOT EXISTS "hash_version" int2 NOT NULL DEFAULT 1;
+
+COMMENT ON COLUMN "public"."account_log"."hash_version" IS '哈希格式版本';
@@ -0,0 +1,32 @@
+-- Add migration script here
+-- 事件id在写入时分配，与提交顺序不一致，发布顺序由发布任务在事件提交后分配
+-- 每次按id顺序为当前已提交且未分配的事件分配发布序号，之后提交的事件序号更大
+CREATE SEQUENCE IF NOT EXISTS "public"."outbox_event_publish_seq";
+
+ALTER TABLE "public"."outbox_event"
+    ADD COLUMN IF NOT EXISTS "publish_seq" bigint UNIQUE;
+
+-- 迁移前未发布的事件由发布任务分配序号，已发布的事件按id补齐
+UPDATE "public"."outbox_event" e
+SET "publish_seq" = s.publish_seq
+FROM (
+    SELECT id, nextval('outbox_event_publish_seq') AS publish_seq
+    FROM "public"."outbox_event"
+    WHERE "published_at" IS NOT NULL
+    ORDER BY id
+) s
+WHERE e.id = s.id;
+
+DROP INDEX IF EXISTS "public"."outbox_event_unpublished_idx";
+
+CREATE INDEX IF NOT EXISTS outbox_event_unsequenced_idx ON "public"."outbox_event"("id")
+WHERE
+    "publish_seq" IS NULL;
+
+CREATE INDEX IF NOT EXISTS outbox_event_unpublished_idx ON "public"."outbox_event"("publish_seq")
+WHERE
+    "published_at" IS NULL;
+
+COMMENT ON COLUMN "public"."outbox_event"."id" IS '主键自增id，写入时分配';
+
+COMMENT ON COLUMN "public"."outbox_event"."publish_seq" IS '发布序号，即发布顺序，提交后由发布任务分配，未分配时为空';
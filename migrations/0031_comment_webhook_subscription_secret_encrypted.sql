@@ -0,0 +1,3 @@
+-- Add migration script here
+-- 回调签名密钥由服务使用`STARDUST_SECRET_KEY`加密后保存，仅在签名时解密，已有的明文密钥在服务启动时加密
+COMMENT ON COLUMN "public"."webhook_subscription"."secret" IS '回调签名密钥（AES-256-GCM加密）';
@@ -4,3 +4,145 @@ pub const MIN_PAGE: i32 = 1;
 pub const MIN_PAGE_SIZE: i32 = 5;
 // 每页最大数量
 pub const MAX_PAGE_SIZE: i32 = 100;
+// 导出时每次从游标读取的记录数
+pub const EXPORT_FETCH_SIZE: i64 = 1000;
+// 导出缓冲的数据块数量，客户端读取较慢时暂停读取游标
+pub const EXPORT_CHANNEL_CAPACITY: usize = 8;
+// 导出等待客户端读取的最长时间（秒），超时后中断导出，释放事务及连接
+pub const EXPORT_SEND_TIMEOUT: u64 = 30;
+// 转账转出操作类型，转账不计入累计收入和累计支出
+pub const TRANSFER_OUT_ACTION_TYPE: &str = "TRF_OUT";
+// 转账转入操作类型
+pub const TRANSFER_IN_ACTION_TYPE: &str = "TRF_IN";
+// 预授权冻结操作类型
+pub const HOLD_AUTHORIZE_ACTION_TYPE: &str = "FRZ";
+// 预授权扣款操作类型
+pub const HOLD_CAPTURE_ACTION_TYPE: &str = "FB_EXP";
+// 预授权释放操作类型
+pub const HOLD_RELEASE_ACTION_TYPE: &str = "UFZ";
+// 转账及预授权按名称使用的内置操作类型，不允许改名、修改变化方向或停用
+pub const BUILTIN_ACTION_TYPES: [&str; 5] = [
+    TRANSFER_OUT_ACTION_TYPE,
+    TRANSFER_IN_ACTION_TYPE,
+    HOLD_AUTHORIZE_ACTION_TYPE,
+    HOLD_CAPTURE_ACTION_TYPE,
+    HOLD_RELEASE_ACTION_TYPE,
+];
+// 预授权默认有效期（秒）
+pub const HOLD_DEFAULT_EXPIRES_IN: i64 = 1800;
+// 预授权最短有效期（秒）
+pub const HOLD_MIN_EXPIRES_IN: i64 = 60;
+// 预授权最长有效期（秒）
+pub const HOLD_MAX_EXPIRES_IN: i64 = 7 * 24 * 3600;
+// 过期预授权扫描间隔（秒）
+pub const HOLD_EXPIRY_INTERVAL: u64 = 30;
+// 每次扫描处理的过期预授权数量
+pub const HOLD_EXPIRY_BATCH_SIZE: i64 = 100;
+// 配置变更通知通道
+pub const CONFIG_CHANGE_CHANNEL: &str = "config_change";
+// 余额变更通知通道
+pub const BALANCE_CHANGE_CHANNEL: &str = "balance_change";
+// 余额变更广播容量，订阅者落后超过该数量时需重新获取余额
+pub const BALANCE_BROADCAST_CAPACITY: usize = 1024;
+// 余额订阅心跳间隔（秒）
+pub const BALANCE_KEEP_ALIVE_INTERVAL: u64 = 15;
+// 缓存定时全量刷新间隔（秒）
+pub const CACHE_RELOAD_INTERVAL: u64 = 60;
+// 监听连接失败后的重试间隔（秒）
+pub const LISTENER_RETRY_INTERVAL: u64 = 5;
+// 管理令牌环境变量
+pub const ADMIN_TOKEN_ENV: &str = "STARDUST_ADMIN_TOKEN";
+// 管理令牌请求头
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+// 监控指标令牌环境变量
+pub const METRICS_TOKEN_ENV: &str = "STARDUST_METRICS_TOKEN";
+// 加密签名密钥的主密钥环境变量（64位十六进制，即32字节）
+pub const SECRET_KEY_ENV: &str = "STARDUST_SECRET_KEY";
+// 已加密签名密钥的前缀
+pub const ENCRYPTED_SECRET_PREFIX: &str = "v1:";
+// 客户端API密钥请求头
+pub const API_KEY_HEADER: &str = "x-api-key";
+// 请求签名时间戳请求头（Unix时间戳，秒）
+pub const SIGNATURE_TIMESTAMP_HEADER: &str = "x-timestamp";
+// 请求签名随机数请求头
+pub const SIGNATURE_NONCE_HEADER: &str = "x-nonce";
+// 请求签名请求头（HMAC-SHA256，十六进制）
+pub const SIGNATURE_HEADER: &str = "x-signature";
+// 请求签名有效期（秒），时间戳偏差超出该值的请求被拒绝，随机数在该期间内不可重复使用
+pub const SIGNATURE_MAX_AGE: i64 = 300;
+// 参与签名的请求体最大长度（字节）
+pub const SIGNATURE_MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+// 过期随机数清理间隔（秒）
+pub const NONCE_CLEANUP_INTERVAL: u64 = 60;
+// 校验哈希链时每批读取的记录数
+pub const CHAIN_VERIFY_BATCH_SIZE: i64 = 1000;
+// 当前哈希格式版本，版本2起`batch_id`参与哈希计算
+pub const CHAIN_HASH_VERSION: i16 = 2;
+// 定时对账间隔（秒）
+pub const RECONCILIATION_INTERVAL: u64 = 3600;
+// 对账时每批读取的账户数量
+pub const RECONCILIATION_BATCH_SIZE: i64 = 500;
+// 定时对账咨询锁名称，多实例部署时只有一个实例执行
+pub const RECONCILIATION_LOCK_NAME: &str = "account_reconciliation";
+// 保留的未来分区月数
+pub const PARTITION_MONTHS_AHEAD: i32 = 5;
+// 分区维护间隔（秒）
+pub const PARTITION_MAINTENANCE_INTERVAL: u64 = 3600;
+// 分区维护咨询锁名称，多实例部署时只有一个实例执行维护
+pub const PARTITION_LOCK_NAME: &str = "account_log_partition";
+// 在线保留的操作记录月数，更早的分区将被归档
+pub const ARCHIVE_RETENTION_MONTHS: u32 = 12;
+// 归档检查间隔（秒）
+pub const ARCHIVE_INTERVAL: u64 = 24 * 3600;
+// 归档目录环境变量
+pub const ARCHIVE_DIR_ENV: &str = "STARDUST_ARCHIVE_DIR";
+// 默认归档目录
+pub const ARCHIVE_DEFAULT_DIR: &str = "archive";
+// 归档咨询锁名称
+pub const ARCHIVE_LOCK_NAME: &str = "account_log_archive";
+// 归档卸载分区时等待表锁的最长时间，超时后放弃，下次归档时重试
+pub const ARCHIVE_LOCK_TIMEOUT: &str = "5s";
+// 归档文件读写缓冲的数据块数量
+pub const ARCHIVE_CHANNEL_CAPACITY: usize = 8;
+// 恢复归档时每次读取的字节数
+pub const ARCHIVE_CHUNK_SIZE: usize = 64 * 1024;
+// 发件箱事件接收地址环境变量，未设置时事件保留在发件箱中不发布
+pub const OUTBOX_ENDPOINT_ENV: &str = "STARDUST_OUTBOX_ENDPOINT";
+// 发件箱轮询间隔（秒）
+pub const OUTBOX_INTERVAL: u64 = 1;
+// 发件箱每批发布的事件数量
+pub const OUTBOX_BATCH_SIZE: i64 = 100;
+// 发件箱咨询锁名称，多实例部署时只有一个实例发布，保证事件顺序
+pub const OUTBOX_LOCK_NAME: &str = "outbox_event_dispatch";
+// 已发布事件保留天数
+pub const OUTBOX_RETENTION_DAYS: i32 = 7;
+// 已发布事件清理间隔（秒）
+pub const OUTBOX_CLEANUP_INTERVAL: u64 = 3600;
+// 发布事件及投递回调的请求超时时间（秒）
+pub const HTTP_TIMEOUT: u64 = 10;
+// 回调投递轮询间隔（秒）
+pub const WEBHOOK_INTERVAL: u64 = 1;
+// 每批投递的回调数量
+pub const WEBHOOK_BATCH_SIZE: i64 = 50;
+// 回调投递租约（秒），领取后在此期间内不会被再次领取，须大于HTTP请求超时时间
+pub const WEBHOOK_LEASE: i64 = 6 * HTTP_TIMEOUT as i64;
+// 回调最多尝试次数，超过后标记为`DEAD`
+pub const WEBHOOK_MAX_ATTEMPTS: i32 = 10;
+// 回调首次重试等待时间（秒），之后每次翻倍
+pub const WEBHOOK_RETRY_BASE: i64 = 30;
+// 回调重试最长等待时间（秒）
+pub const WEBHOOK_RETRY_MAX: i64 = 6 * 3600;
+// 回调事件id请求头
+pub const WEBHOOK_ID_HEADER: &str = "x-webhook-id";
+// 回调时间戳请求头
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+// 回调签名请求头
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-webhook-signature";
+// 查询投递记录的最大数量
+pub const WEBHOOK_DELIVERY_LIMIT: i64 = 100;
+// 就绪检查访问数据库的超时时间（秒）
+pub const READINESS_DB_TIMEOUT: u64 = 2;
+// 死锁或序列化失败时事务最多重试次数
+pub const TRANSACTION_MAX_RETRIES: u64 = 3;
+// 事务重试基础等待时间（毫秒），每次重试递增并附加随机等待
+pub const TRANSACTION_RETRY_DELAY: u64 = 20;
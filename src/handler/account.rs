@@ -1,47 +1,177 @@
 use crate::{
-    model::{account::AccountModel, account_log::AccountLogModel},
-    request::{AccountActionRequest, AccountLogRequest, AccountRequest, AccountsRequest},
-    service::account::AccountService,
+    constant::BALANCE_KEEP_ALIVE_INTERVAL,
+    model::{
+        account::AccountModel,
+        account_log::{AccountLogModel, OrderLogModel},
+        client::ClientModel,
+        transfer::TransferModel,
+    },
+    request::{
+        AccountActionRequest, AccountActionsQuery, AccountActivityRequest, AccountBatchRequest,
+        AccountLogExportRequest, AccountLogRequest, AccountRequest, AccountReversalRequest,
+        AccountSubscribeRequest, AccountTransferRequest, AccountsRequest, OrderLogRequest,
+    },
+    response::{
+        AccountActionsResponse, AccountActivityResponse, AccountLogsResponse, ChainVerification,
+    },
+    service::{account::AccountService, balance_stream::BalanceStreamService},
+};
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::Query,
+    http::{StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{KeepAlive, Sse},
+    },
 };
-use axum::{Json, http::StatusCode};
 use axum_kit::{AppResult, validation::ValidatedJson};
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
 
 // 添加账户
 pub async fn create(
+    Extension(client): Extension<ClientModel>,
     ValidatedJson(payload): ValidatedJson<AccountRequest>,
 ) -> AppResult<(StatusCode, Json<AccountModel>)> {
-    let account = AccountService::create(&payload).await?;
+    let account = AccountService::create(&client, &payload).await?;
     Ok((StatusCode::CREATED, Json(account)))
 }
 
 // 账户信息
 pub async fn info(
+    Extension(client): Extension<ClientModel>,
     ValidatedJson(payload): ValidatedJson<AccountRequest>,
 ) -> AppResult<Json<AccountModel>> {
-    let account = AccountService::info(&payload).await?;
+    let account = AccountService::info(&client, &payload).await?;
     Ok(Json(account))
 }
 
 // 某`user_id`所有账户信息
 pub async fn infos(
+    Extension(client): Extension<ClientModel>,
     ValidatedJson(payload): ValidatedJson<AccountsRequest>,
 ) -> AppResult<Json<Vec<AccountModel>>> {
-    let accounts = AccountService::infos(&payload).await?;
+    let accounts = AccountService::infos(&client, &payload).await?;
     Ok(Json(accounts))
 }
 
 // 账户操作
 // 仅涉及可用余额、冻结余额、累计收入、累计支出的变更
+// 以客户端+`order_number`作为幂等键，重试时返回首次处理结果
+// `?preview=true`时仅预览，返回各操作变更后的余额或失败原因
 pub async fn actions(
+    Extension(client): Extension<ClientModel>,
+    Query(query): Query<AccountActionsQuery>,
     ValidatedJson(payload): ValidatedJson<Vec<AccountActionRequest>>,
-) -> AppResult<()> {
-    AccountService::actions(&payload).await
+) -> AppResult<Response> {
+    if query.preview {
+        let preview = AccountService::preview(&client, &payload).await?;
+        return Ok(Json(preview).into_response());
+    }
+    let response = AccountService::actions(&client, &payload).await?;
+    Ok(Json(response).into_response())
+}
+
+// 按批次id查询批量操作结果
+pub async fn batch(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<AccountBatchRequest>,
+) -> AppResult<Json<AccountActionsResponse>> {
+    let response = AccountService::batch(&client, &payload).await?;
+    Ok(Json(response))
+}
+
+// 用户间转账
+pub async fn transfer(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<AccountTransferRequest>,
+) -> AppResult<(StatusCode, Json<TransferModel>)> {
+    let transfer = AccountService::transfer(&client, &payload).await?;
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+// 按订单号冲正账户操作
+pub async fn reverse(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<AccountReversalRequest>,
+) -> AppResult<Json<Vec<AccountLogModel>>> {
+    let account_logs = AccountService::reverse(&client, &payload).await?;
+    Ok(Json(account_logs))
 }
 
 // 账户操作记录
+// 同时返回记录总数、金额变动合计、期初期末余额及下一页游标
 pub async fn logs(
+    Extension(client): Extension<ClientModel>,
     ValidatedJson(payload): ValidatedJson<AccountLogRequest>,
-) -> AppResult<Json<Vec<AccountLogModel>>> {
-    let account_logs = AccountService::logs(&payload).await?;
+) -> AppResult<Json<AccountLogsResponse>> {
+    let account_logs = AccountService::logs(&client, &payload).await?;
     Ok(Json(account_logs))
 }
+
+// 用户全部资产账户的操作记录
+// 按时间倒序合并，每条记录附带所属资产类型
+pub async fn activity(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<AccountActivityRequest>,
+) -> AppResult<Json<AccountActivityResponse>> {
+    let account_activity = AccountService::activity(&client, &payload).await?;
+    Ok(Json(account_activity))
+}
+
+// 导出账户操作记录
+// 按CSV或NDJSON格式流式输出全部符合条件的记录，不分页
+pub async fn export(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<AccountLogExportRequest>,
+) -> AppResult<impl IntoResponse> {
+    let receiver = AccountService::export(&client, &payload).await?;
+    let file_name = format!(
+        "account_log_{}_{}.{}",
+        payload.user_id,
+        payload.asset_type_id,
+        payload.format.extension()
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                payload.format.content_type().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    ))
+}
+
+// 订阅余额变更（Server-Sent Events）
+pub async fn subscribe(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<AccountSubscribeRequest>,
+) -> AppResult<impl IntoResponse> {
+    let stream = BalanceStreamService::subscribe(&client, &payload).await?;
+    Ok(Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(BALANCE_KEEP_ALIVE_INTERVAL))))
+}
+
+// 按订单号跨账户查询操作记录
+pub async fn order_logs(
+    ValidatedJson(payload): ValidatedJson<OrderLogRequest>,
+) -> AppResult<Json<Vec<OrderLogModel>>> {
+    let logs = AccountService::order_logs(&payload).await?;
+    Ok(Json(logs))
+}
+
+// 校验账户哈希链
+// 返回第一处断裂的记录，哈希链完整时为空
+pub async fn verify_chain(
+    ValidatedJson(payload): ValidatedJson<AccountRequest>,
+) -> AppResult<Json<ChainVerification>> {
+    let verification = AccountService::verify_chain(&payload).await?;
+    Ok(Json(verification))
+}
@@ -1,9 +1,45 @@
-use crate::{model::action_type::ActionTypeModel, service::action_type::ActionTypeService};
-use axum::Json;
-use axum_kit::AppResult;
+use crate::{
+    model::action_type::ActionTypeModel,
+    request::{ActionTypeActiveRequest, ActionTypeCreateRequest, ActionTypeUpdateRequest},
+    service::action_type::ActionTypeService,
+};
+use axum::{Json, http::StatusCode};
+use axum_kit::{AppResult, validation::ValidatedJson};
 
 // 账户操作类型列表
-pub async fn list() -> AppResult<Json<&'static Vec<ActionTypeModel>>> {
-    let action_type = ActionTypeService::list();
+pub async fn list() -> AppResult<Json<Vec<ActionTypeModel>>> {
+    let action_type = ActionTypeService::list()?;
+    Ok(Json(action_type))
+}
+
+// 添加操作类型
+pub async fn create(
+    ValidatedJson(payload): ValidatedJson<ActionTypeCreateRequest>,
+) -> AppResult<(StatusCode, Json<ActionTypeModel>)> {
+    let action_type = ActionTypeService::create(&payload).await?;
+    Ok((StatusCode::CREATED, Json(action_type)))
+}
+
+// 修改操作类型
+pub async fn update(
+    ValidatedJson(payload): ValidatedJson<ActionTypeUpdateRequest>,
+) -> AppResult<Json<ActionTypeModel>> {
+    let action_type = ActionTypeService::update(&payload).await?;
+    Ok(Json(action_type))
+}
+
+// 启用操作类型
+pub async fn activate(
+    ValidatedJson(payload): ValidatedJson<ActionTypeActiveRequest>,
+) -> AppResult<Json<ActionTypeModel>> {
+    let action_type = ActionTypeService::activate(&payload).await?;
+    Ok(Json(action_type))
+}
+
+// 停用操作类型
+pub async fn deactivate(
+    ValidatedJson(payload): ValidatedJson<ActionTypeActiveRequest>,
+) -> AppResult<Json<ActionTypeModel>> {
+    let action_type = ActionTypeService::deactivate(&payload).await?;
     Ok(Json(action_type))
 }
@@ -0,0 +1,33 @@
+use crate::{
+    model::archive::ArchiveManifest, request::ArchiveMonthRequest, service::archive::ArchiveService,
+};
+use axum::Json;
+use axum_kit::{AppResult, validation::ValidatedJson};
+
+// 归档清单
+pub async fn list() -> AppResult<Json<Vec<ArchiveManifest>>> {
+    let manifests = ArchiveService::list().await?;
+    Ok(Json(manifests))
+}
+
+// 立即归档超出保留期的分区
+pub async fn run() -> AppResult<Json<Vec<ArchiveManifest>>> {
+    let manifests = ArchiveService::run().await?;
+    Ok(Json(manifests))
+}
+
+// 重新挂载已归档的月份
+pub async fn restore(
+    ValidatedJson(payload): ValidatedJson<ArchiveMonthRequest>,
+) -> AppResult<Json<ArchiveManifest>> {
+    let manifest = ArchiveService::restore(&payload).await?;
+    Ok(Json(manifest))
+}
+
+// 卸载重新挂载的已归档月份
+pub async fn unload(
+    ValidatedJson(payload): ValidatedJson<ArchiveMonthRequest>,
+) -> AppResult<Json<ArchiveManifest>> {
+    let manifest = ArchiveService::unload(&payload).await?;
+    Ok(Json(manifest))
+}
@@ -1,9 +1,45 @@
-use crate::{model::asset_type::AssetTypeModel, service::asset_type::AssetTypeService};
-use axum::Json;
-use axum_kit::AppResult;
+use crate::{
+    model::asset_type::AssetTypeModel,
+    request::{AssetTypeActiveRequest, AssetTypeCreateRequest, AssetTypeUpdateRequest},
+    service::asset_type::AssetTypeService,
+};
+use axum::{Json, http::StatusCode};
+use axum_kit::{AppResult, validation::ValidatedJson};
 
 // 资产类型列表
-pub async fn list() -> AppResult<Json<&'static Vec<AssetTypeModel>>> {
-    let asset_type = AssetTypeService::list();
+pub async fn list() -> AppResult<Json<Vec<AssetTypeModel>>> {
+    let asset_type = AssetTypeService::list()?.to_vec();
+    Ok(Json(asset_type))
+}
+
+// 添加资产类型
+pub async fn create(
+    ValidatedJson(payload): ValidatedJson<AssetTypeCreateRequest>,
+) -> AppResult<(StatusCode, Json<AssetTypeModel>)> {
+    let asset_type = AssetTypeService::create(&payload).await?;
+    Ok((StatusCode::CREATED, Json(asset_type)))
+}
+
+// 修改资产类型
+pub async fn update(
+    ValidatedJson(payload): ValidatedJson<AssetTypeUpdateRequest>,
+) -> AppResult<Json<AssetTypeModel>> {
+    let asset_type = AssetTypeService::update(&payload).await?;
+    Ok(Json(asset_type))
+}
+
+// 启用资产类型
+pub async fn activate(
+    ValidatedJson(payload): ValidatedJson<AssetTypeActiveRequest>,
+) -> AppResult<Json<AssetTypeModel>> {
+    let asset_type = AssetTypeService::activate(&payload).await?;
+    Ok(Json(asset_type))
+}
+
+// 停用资产类型
+pub async fn deactivate(
+    ValidatedJson(payload): ValidatedJson<AssetTypeActiveRequest>,
+) -> AppResult<Json<AssetTypeModel>> {
+    let asset_type = AssetTypeService::deactivate(&payload).await?;
     Ok(Json(asset_type))
 }
@@ -0,0 +1,32 @@
+use crate::{
+    model::client::ClientModel,
+    request::{ClientCreateRequest, ClientSigningRequest, ClientUpdateRequest},
+    response::{ClientCreatedResponse, ClientSigningResponse},
+    service::client::ClientService,
+};
+use axum::{Json, http::StatusCode};
+use axum_kit::{AppResult, validation::ValidatedJson};
+
+// 添加客户端
+pub async fn create(
+    ValidatedJson(payload): ValidatedJson<ClientCreateRequest>,
+) -> AppResult<(StatusCode, Json<ClientCreatedResponse>)> {
+    let client = ClientService::create(&payload).await?;
+    Ok((StatusCode::CREATED, Json(client)))
+}
+
+// 修改客户端权限或启用状态
+pub async fn update(
+    ValidatedJson(payload): ValidatedJson<ClientUpdateRequest>,
+) -> AppResult<Json<ClientModel>> {
+    let client = ClientService::update(&payload).await?;
+    Ok(Json(client))
+}
+
+// 启用或停用客户端请求签名
+pub async fn signing(
+    ValidatedJson(payload): ValidatedJson<ClientSigningRequest>,
+) -> AppResult<Json<ClientSigningResponse>> {
+    let client = ClientService::signing(&payload).await?;
+    Ok(Json(client))
+}
@@ -0,0 +1,19 @@
+use crate::{response::ReadinessReport, service::health::HealthService};
+use axum::{Json, http::StatusCode};
+use serde_json::{Value, json};
+
+// 存活检查
+pub async fn healthz() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+// 就绪检查，未就绪时返回503
+pub async fn readyz() -> (StatusCode, Json<ReadinessReport>) {
+    let report = HealthService::readiness().await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
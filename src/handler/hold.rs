@@ -0,0 +1,45 @@
+use crate::{
+    model::{client::ClientModel, hold::HoldModel},
+    request::{HoldAuthorizeRequest, HoldCaptureRequest, HoldRequest, HoldVoidRequest},
+    service::hold::HoldService,
+};
+use axum::{Extension, Json, http::StatusCode};
+use axum_kit::{AppResult, validation::ValidatedJson};
+
+// 预授权冻结
+pub async fn authorize(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<HoldAuthorizeRequest>,
+) -> AppResult<(StatusCode, Json<HoldModel>)> {
+    let hold = HoldService::authorize(&client, &payload).await?;
+    Ok((StatusCode::CREATED, Json(hold)))
+}
+
+// 预授权扣款
+// 支持多次部分扣款，剩余金额为零时预授权结束
+pub async fn capture(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<HoldCaptureRequest>,
+) -> AppResult<Json<HoldModel>> {
+    let hold = HoldService::capture(&client, &payload).await?;
+    Ok(Json(hold))
+}
+
+// 预授权撤销
+// 释放剩余冻结金额
+pub async fn void(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<HoldVoidRequest>,
+) -> AppResult<Json<HoldModel>> {
+    let hold = HoldService::void(&client, &payload).await?;
+    Ok(Json(hold))
+}
+
+// 预授权信息
+pub async fn info(
+    Extension(client): Extension<ClientModel>,
+    ValidatedJson(payload): ValidatedJson<HoldRequest>,
+) -> AppResult<Json<HoldModel>> {
+    let hold = HoldService::info(&client, &payload).await?;
+    Ok(Json(hold))
+}
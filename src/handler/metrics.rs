@@ -0,0 +1,12 @@
+use crate::service::metrics::MetricsService;
+use axum::http::header;
+use axum_kit::AppResult;
+
+// Prometheus 指标
+pub async fn render() -> AppResult<([(header::HeaderName, &'static str); 1], String)> {
+    let metrics = MetricsService::render()?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics,
+    ))
+}
@@ -1,3 +1,11 @@
 pub mod account;
 pub mod action_type;
+pub mod archive;
 pub mod asset_type;
+pub mod client;
+pub mod health;
+pub mod hold;
+pub mod metrics;
+pub mod partition;
+pub mod reconciliation;
+pub mod webhook;
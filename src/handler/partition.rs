@@ -0,0 +1,9 @@
+use crate::{response::PartitionStatus, service::partition::PartitionService};
+use axum::Json;
+use axum_kit::AppResult;
+
+// 分区状态
+pub async fn status() -> AppResult<Json<PartitionStatus>> {
+    let status = PartitionService::status().await?;
+    Ok(Json(status))
+}
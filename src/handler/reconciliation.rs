@@ -0,0 +1,15 @@
+use crate::{
+    request::ReconciliationRequest, response::ReconciliationReport,
+    service::reconciliation::ReconciliationService,
+};
+use axum::Json;
+use axum_kit::{AppResult, validation::ValidatedJson};
+
+// 对账
+// 返回所有余额与操作记录不一致的账户字段
+pub async fn run(
+    ValidatedJson(payload): ValidatedJson<ReconciliationRequest>,
+) -> AppResult<Json<ReconciliationReport>> {
+    let report = ReconciliationService::run(&payload).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,43 @@
+use crate::{
+    model::webhook::{WebhookDeliveryModel, WebhookSubscriptionModel},
+    request::{
+        WebhookCreateRequest, WebhookDeliveriesRequest, WebhookRedeliverRequest,
+        WebhookUpdateRequest,
+    },
+    response::WebhookCreatedResponse,
+    service::webhook::WebhookService,
+};
+use axum::{Json, http::StatusCode};
+use axum_kit::{AppResult, validation::ValidatedJson};
+
+// 添加回调订阅
+pub async fn create(
+    ValidatedJson(payload): ValidatedJson<WebhookCreateRequest>,
+) -> AppResult<(StatusCode, Json<WebhookCreatedResponse>)> {
+    let subscription = WebhookService::create(&payload).await?;
+    Ok((StatusCode::CREATED, Json(subscription)))
+}
+
+// 修改回调订阅
+pub async fn update(
+    ValidatedJson(payload): ValidatedJson<WebhookUpdateRequest>,
+) -> AppResult<Json<WebhookSubscriptionModel>> {
+    let subscription = WebhookService::update(&payload).await?;
+    Ok(Json(subscription))
+}
+
+// 投递记录
+pub async fn deliveries(
+    ValidatedJson(payload): ValidatedJson<WebhookDeliveriesRequest>,
+) -> AppResult<Json<Vec<WebhookDeliveryModel>>> {
+    let deliveries = WebhookService::deliveries(&payload).await?;
+    Ok(Json(deliveries))
+}
+
+// 重新投递
+pub async fn redeliver(
+    ValidatedJson(payload): ValidatedJson<WebhookRedeliverRequest>,
+) -> AppResult<Json<WebhookDeliveryModel>> {
+    let delivery = WebhookService::redeliver(&payload).await?;
+    Ok(Json(delivery))
+}
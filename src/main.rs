@@ -1,7 +1,9 @@
 mod constant;
 mod handler;
+mod middleware;
 mod model;
 mod request;
+mod response;
 mod route;
 mod service;
 mod utils;
@@ -12,8 +14,36 @@ async fn main() -> anyhow::Result<()> {
         .with_router(route::api::init)
         .before_run(|| {
             tokio::spawn(async move {
-                service::asset_type::AssetTypeService::init().await?;
-                service::action_type::ActionTypeService::init().await?;
+                // 初始化失败时仍启动后台任务，由定时任务重试，完成前`/readyz`返回未就绪
+                if let Err(e) = service::partition::PartitionService::maintain().await {
+                    tracing::error!("分区维护失败: {}", e);
+                }
+                if let Err(e) = service::asset_type::AssetTypeService::init().await {
+                    tracing::error!("加载资产类型失败: {}", e);
+                }
+                if let Err(e) = service::action_type::ActionTypeService::init().await {
+                    tracing::error!("加载操作类型失败: {}", e);
+                }
+                match service::client::ClientService::encrypt_signing_secrets().await {
+                    Ok(0) => {}
+                    Ok(encrypted) => tracing::info!("已加密客户端签名密钥: {}", encrypted),
+                    Err(e) => tracing::error!("加密客户端签名密钥失败: {}", e),
+                }
+                match service::webhook::WebhookService::encrypt_secrets().await {
+                    Ok(0) => {}
+                    Ok(encrypted) => tracing::info!("已加密回调签名密钥: {}", encrypted),
+                    Err(e) => tracing::error!("加密回调签名密钥失败: {}", e),
+                }
+                tokio::spawn(service::cache::CacheService::run_reload_task());
+                tokio::spawn(service::balance_stream::BalanceStreamService::run_listen_task());
+                tokio::spawn(service::hold::HoldService::run_expiry_task());
+                tokio::spawn(service::signature::SignatureService::run_nonce_cleanup_task());
+                tokio::spawn(service::reconciliation::ReconciliationService::run_task());
+                tokio::spawn(service::partition::PartitionService::run_maintenance_task());
+                tokio::spawn(service::archive::ArchiveService::run_task());
+                tokio::spawn(service::outbox::OutboxService::run_dispatch_task());
+                tokio::spawn(service::outbox::OutboxService::run_cleanup_task());
+                tokio::spawn(service::webhook::WebhookService::run_delivery_task());
                 Ok(())
             })
         })
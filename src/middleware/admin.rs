@@ -0,0 +1,25 @@
+use crate::{
+    constant::{ADMIN_TOKEN_ENV, ADMIN_TOKEN_HEADER},
+    utils,
+};
+use axum::{extract::Request, http::StatusCode, middleware::Next, response::Response};
+use axum_kit::{AppResult, error::Error};
+
+// 管理接口鉴权
+// 请求头`x-admin-token`需与环境变量`STARDUST_ADMIN_TOKEN`一致，未配置环境变量时拒绝所有管理请求
+pub async fn auth(request: Request, next: Next) -> AppResult<Response> {
+    let expected = std::env::var(ADMIN_TOKEN_ENV).unwrap_or_default();
+    let provided = request
+        .headers()
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    // 比较摘要而非原文，避免按字节比较泄露令牌长度和前缀
+    if expected.is_empty() || utils::sha256_hex(expected) != utils::sha256_hex(provided) {
+        return Err(Error::Custom(
+            StatusCode::UNAUTHORIZED,
+            "未授权的管理请求".to_string(),
+        ));
+    }
+    Ok(next.run(request).await)
+}
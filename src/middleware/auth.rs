@@ -0,0 +1,16 @@
+use crate::{constant::API_KEY_HEADER, service::client::ClientService};
+use axum::{extract::Request, middleware::Next, response::Response};
+use axum_kit::AppResult;
+
+// 客户端鉴权
+// 请求头`x-api-key`需对应已启用的客户端，鉴权通过后将客户端信息写入请求扩展
+pub async fn api_key(mut request: Request, next: Next) -> AppResult<Response> {
+    let api_key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let client = ClientService::authenticate(api_key).await?;
+    request.extensions_mut().insert(client);
+    Ok(next.run(request).await)
+}
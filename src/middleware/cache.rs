@@ -0,0 +1,11 @@
+use crate::service::{action_type::ActionTypeService, asset_type::AssetTypeService};
+use axum::{extract::Request, middleware::Next, response::Response};
+use axum_kit::AppResult;
+
+// 资产类型及操作类型缓存加载前返回503
+// 须在请求体校验前执行，校验时依赖缓存判断类型是否有效
+pub async fn ready(request: Request, next: Next) -> AppResult<Response> {
+    AssetTypeService::list()?;
+    ActionTypeService::list_all()?;
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,43 @@
+use crate::{constant::METRICS_TOKEN_ENV, service::metrics::MetricsService, utils};
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use axum_kit::{AppResult, error::Error};
+use std::time::Instant;
+
+// 记录请求数量和耗时，流式响应的耗时为返回响应头的时间
+pub async fn track(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let start = Instant::now();
+    let response = next.run(request).await;
+    MetricsService::observe_request(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
+
+// 监控指标鉴权，与管理令牌分开，便于只授予 Prometheus 读取指标的权限
+// 请求头`Authorization: Bearer <token>`需与环境变量`STARDUST_METRICS_TOKEN`一致，未配置环境变量时拒绝所有请求
+pub async fn auth(request: Request, next: Next) -> AppResult<Response> {
+    let expected = std::env::var(METRICS_TOKEN_ENV).unwrap_or_default();
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    // 比较摘要而非原文，避免按字节比较泄露令牌长度和前缀
+    if expected.is_empty() || utils::sha256_hex(expected) != utils::sha256_hex(provided) {
+        return Err(Error::Custom(
+            StatusCode::UNAUTHORIZED,
+            "未授权的指标请求".to_string(),
+        ));
+    }
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,5 @@
+pub mod admin;
+pub mod auth;
+pub mod cache;
+pub mod metrics;
+pub mod signature;
@@ -0,0 +1,38 @@
+use crate::{
+    constant::SIGNATURE_MAX_BODY_SIZE, model::client::ClientModel,
+    service::signature::SignatureService,
+};
+use axum::{
+    body::{self, Body},
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use axum_kit::{AppResult, error::Error};
+
+// 请求签名校验
+// 仅对配置了签名密钥的客户端生效，需在客户端鉴权之后执行
+pub async fn verify(request: Request, next: Next) -> AppResult<Response> {
+    let Some(client) = request
+        .extensions()
+        .get::<ClientModel>()
+        .filter(|client| client.signing_secret.is_some())
+        .cloned()
+    else {
+        return Ok(next.run(request).await);
+    };
+    // 签名覆盖请求体，需先完整读取再交给后续处理
+    let (parts, body) = request.into_parts();
+    let bytes = body::to_bytes(body, SIGNATURE_MAX_BODY_SIZE)
+        .await
+        .map_err(|_| Error::Custom(StatusCode::PAYLOAD_TOO_LARGE, "请求体过大".to_string()))?;
+    let path = parts
+        .uri
+        .path_and_query()
+        .map_or(parts.uri.path(), |path_and_query| path_and_query.as_str());
+    SignatureService::verify(&client, parts.method.as_str(), path, &parts.headers, &bytes).await?;
+    Ok(next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await)
+}
@@ -26,7 +26,28 @@ pub struct AccountModel {
     pub updated_at: DateTime<Utc>,
 }
 
+// 哈希链锚点，与余额在同一行锁下更新
+pub struct ChainAnchor {
+    // 第一条在线记录的id，没有哈希记录时为空
+    pub start_log_id: Option<i64>,
+    // 最后一条记录的哈希
+    pub last_log_hash: Option<String>,
+}
+
 impl AccountModel {
+    // 通知余额变更，内容为账户信息，事务提交后才会发送
+    pub async fn notify(
+        executor: impl PgExecutor<'_>,
+        channel: &str,
+        account: &Self,
+    ) -> AppResult<()> {
+        let payload = serde_json::to_string(account).map_err(anyhow::Error::from)?;
+        sqlx::query!(r#"select pg_notify($1, $2)"#, channel, payload)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
     pub async fn create(
         executor: impl PgExecutor<'_>,
         user_id: i32,
@@ -193,6 +214,76 @@ impl AccountModel {
         Ok(account)
     }
 
+    // 写入账户操作记录后推进哈希链锚点，须在持有账户行锁的事务内调用
+    pub async fn advance_chain(
+        executor: impl PgExecutor<'_>,
+        account_id: i32,
+        log_id: i64,
+        hash: Option<&str>,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            r#"update account
+                set chain_start_log_id = coalesce(chain_start_log_id, $2),
+                last_log_hash = $3
+            where
+                id = $1"#,
+            account_id,
+            log_id,
+            hash
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn chain_anchor(
+        executor: impl PgExecutor<'_>,
+        account_id: i32,
+    ) -> AppResult<ChainAnchor> {
+        let anchor = sqlx::query_as!(
+            ChainAnchor,
+            r#"select
+                chain_start_log_id as start_log_id,
+                last_log_hash
+            from
+                account
+            where
+                id = $1"#,
+            account_id
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(anchor)
+    }
+
+    // 分区删除后，起始记录已不在线的账户改以第一条在线的哈希记录为起点
+    // 全部记录均已归档时置空，下一条记录写入时重新设置
+    pub async fn reset_chain_start(executor: impl PgExecutor<'_>) -> AppResult<()> {
+        sqlx::query!(
+            r#"update account
+                set chain_start_log_id = (
+                    select min(account_log.id)
+                    from account_log
+                    where
+                        account_log.account_id = account.id
+                        and account_log.id > account.chain_start_log_id
+                        and account_log.hash is not null
+                )
+            where
+                chain_start_log_id is not null
+                and not exists (
+                    select 1
+                    from account_log
+                    where
+                        account_log.account_id = account.id
+                        and account_log.id = account.chain_start_log_id
+                )"#
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
     // 资产账户是否存在
     pub async fn is_exists(
         executor: impl PgExecutor<'_>,
@@ -212,6 +303,21 @@ impl AccountModel {
         false
     }
 
+    // 是否存在指定资产类型下余额不为零的账户
+    pub async fn has_nonzero_balance(executor: impl PgExecutor<'_>, asset_type_id: i32) -> bool {
+        if let Ok(Some(exists)) = sqlx::query_scalar!(
+            r#"select exists(select 1 from account where asset_type_id = $1 and (available_balance <> 0 or frozen_balance <> 0))"#,
+            asset_type_id
+        )
+        .fetch_one(executor)
+        .await
+        {
+            return exists;
+        }
+        // 查询失败时按存在处理，避免误停用
+        true
+    }
+
     // 资产账户是否启用
     // #[allow(dead_code)]
     // pub async fn is_active(
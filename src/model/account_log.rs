@@ -1,8 +1,9 @@
 use super::serialize_utc_to_session_tz;
+use crate::{constant::CHAIN_HASH_VERSION, request::AccountActionRequest, utils};
 use axum_kit::AppResult;
 use serde::Serialize;
 use sqlx::{
-    PgExecutor, QueryBuilder,
+    PgConnection, PgExecutor, Postgres, QueryBuilder,
     types::{
         Decimal,
         chrono::{DateTime, Utc},
@@ -28,14 +29,141 @@ pub struct AccountLogModel {
     pub total_expense_after: Decimal,
     pub order_number: String,
     pub description: String,
+    pub transfer_id: Option<i64>,
+    pub reversed_log_id: Option<i64>,
+    pub client_id: Option<i32>,
+    pub batch_id: Option<i64>,
+    #[serde(serialize_with = "serialize_utc_to_session_tz")]
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing)]
+    pub prev_hash: Option<String>,
+    #[serde(skip_serializing)]
+    pub hash: Option<String>,
+    #[serde(skip_serializing)]
+    pub hash_version: i16,
+}
+
+// 导出记录，附带操作类型名称
+#[derive(Serialize, sqlx::FromRow)]
+pub struct AccountLogExportRow {
+    #[serde(serialize_with = "serialize_utc_to_session_tz")]
+    pub created_at: DateTime<Utc>,
+    pub order_number: String,
+    pub action_type_id: i32,
+    pub action_type_name: String,
+    pub amount_available_balance: Decimal,
+    pub amount_frozen_balance: Decimal,
+    pub amount_total_income: Decimal,
+    pub amount_total_expense: Decimal,
+    pub available_balance_after: Decimal,
+    pub frozen_balance_after: Decimal,
+    pub total_income_after: Decimal,
+    pub total_expense_after: Decimal,
+    pub description: String,
+    pub transfer_id: Option<i64>,
+    pub reversed_log_id: Option<i64>,
+    pub client_id: Option<i32>,
+    pub batch_id: Option<i64>,
+}
+
+// 账户日志关联信息
+#[derive(Default, Clone, Copy)]
+pub struct AccountLogContext {
+    // 转账id
+    pub transfer_id: Option<i64>,
+    // 被冲正的日志id
+    pub reversed_log_id: Option<i64>,
+    // 客户端id
+    pub client_id: Option<i32>,
+    // 批次id
+    pub batch_id: Option<i64>,
+}
+
+// 按订单号查询的操作记录，附带用户id及资产类型、操作类型名称
+#[derive(Serialize)]
+pub struct OrderLogModel {
+    pub id: i64,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    pub asset_type_name: String,
+    pub action_type_id: i32,
+    pub action_type_name: String,
+    pub amount_available_balance: Decimal,
+    pub amount_frozen_balance: Decimal,
+    pub amount_total_income: Decimal,
+    pub amount_total_expense: Decimal,
+    pub available_balance_after: Decimal,
+    pub frozen_balance_after: Decimal,
+    pub total_income_after: Decimal,
+    pub total_expense_after: Decimal,
+    pub order_number: String,
+    pub description: String,
+    pub transfer_id: Option<i64>,
+    pub reversed_log_id: Option<i64>,
+    pub client_id: Option<i32>,
+    pub batch_id: Option<i64>,
     #[serde(serialize_with = "serialize_utc_to_session_tz")]
     pub created_at: DateTime<Utc>,
 }
 
+// 批量操作中单个操作的结果，余额为操作后的账户余额
+#[derive(Serialize)]
+pub struct BatchActionResult {
+    pub log_id: i64,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    pub action_type_id: i32,
+    pub order_number: String,
+    pub available_balance: Decimal,
+    pub frozen_balance: Decimal,
+    pub total_income: Decimal,
+    pub total_expense: Decimal,
+}
+
+impl BatchActionResult {
+    pub fn new(
+        account_action_request: &AccountActionRequest,
+        account_log: &AccountLogModel,
+    ) -> Self {
+        Self {
+            log_id: account_log.id,
+            user_id: account_action_request.user_id,
+            asset_type_id: account_action_request.asset_type_id,
+            action_type_id: account_log.action_type_id,
+            order_number: account_log.order_number.clone(),
+            available_balance: account_log.available_balance_after,
+            frozen_balance: account_log.frozen_balance_after,
+            total_income: account_log.total_income_after,
+            total_expense: account_log.total_expense_after,
+        }
+    }
+}
+
+// 账户操作记录统计
+#[derive(Serialize, sqlx::FromRow)]
+pub struct AccountLogSummary {
+    // 记录总数
+    pub total: i64,
+    pub amount_available_balance: Decimal,
+    pub amount_frozen_balance: Decimal,
+    pub amount_total_income: Decimal,
+    pub amount_total_expense: Decimal,
+}
+
+// 某一时刻的账户余额
+#[derive(Serialize, Default)]
+pub struct AccountBalance {
+    pub available_balance: Decimal,
+    pub frozen_balance: Decimal,
+    pub total_income: Decimal,
+    pub total_expense: Decimal,
+}
+
 impl AccountLogModel {
+    // 写入记录并接入账户哈希链，需在持有账户行锁的事务内调用
     #[allow(clippy::too_many_arguments)]
     pub async fn create(
-        executor: impl PgExecutor<'_>,
+        conn: &mut PgConnection,
         account_id: i32,
         action_type_id: i32,
         amount_available_balance: Decimal,
@@ -48,8 +176,50 @@ impl AccountLogModel {
         total_expense_after: Decimal,
         order_number: &str,
         description: &str,
-    ) -> AppResult<()> {
-        sqlx::query!(
+        context: AccountLogContext,
+    ) -> AppResult<Self> {
+        // 显式写入创建时间，保证参与哈希的时间与存储的时间一致
+        let chain = sqlx::query!(
+            r#"select
+                now() as "created_at!",
+                (
+                    select
+                        hash
+                    from
+                        account_log
+                    where
+                        account_id = $1
+                    order by
+                        id desc
+                    limit 1
+                ) as prev_hash"#,
+            account_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        let hash = ChainHashInput {
+            account_id,
+            action_type_id,
+            amount_available_balance,
+            amount_frozen_balance,
+            amount_total_income,
+            amount_total_expense,
+            available_balance_after,
+            frozen_balance_after,
+            total_income_after,
+            total_expense_after,
+            order_number,
+            description,
+            transfer_id: context.transfer_id,
+            reversed_log_id: context.reversed_log_id,
+            client_id: context.client_id,
+            batch_id: context.batch_id,
+            created_at: chain.created_at,
+            prev_hash: chain.prev_hash.as_deref(),
+        }
+        .hash(CHAIN_HASH_VERSION)?;
+        let account_log = sqlx::query_as!(
+            Self,
             r#"insert into account_log(
                 account_id,
                 action_type_id,
@@ -62,9 +232,39 @@ impl AccountLogModel {
                 total_income_after,
                 total_expense_after,
                 order_number,
-                description
+                description,
+                transfer_id,
+                reversed_log_id,
+                client_id,
+                batch_id,
+                created_at,
+                prev_hash,
+                hash,
+                hash_version
             )
-            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+            returning
+                id,
+                account_id,
+                action_type_id,
+                amount_available_balance,
+                amount_frozen_balance,
+                amount_total_income,
+                amount_total_expense,
+                available_balance_after,
+                frozen_balance_after,
+                total_income_after,
+                total_expense_after,
+                order_number,
+                description,
+                transfer_id,
+                reversed_log_id,
+                client_id,
+                batch_id,
+                created_at,
+                prev_hash,
+                hash,
+                hash_version"#,
             account_id,
             action_type_id,
             amount_available_balance,
@@ -76,11 +276,187 @@ impl AccountLogModel {
             total_income_after,
             total_expense_after,
             order_number,
-            description
+            description,
+            context.transfer_id,
+            context.reversed_log_id,
+            context.client_id,
+            context.batch_id,
+            chain.created_at,
+            chain.prev_hash,
+            hash,
+            CHAIN_HASH_VERSION
         )
-        .execute(executor)
+        .fetch_one(conn)
         .await?;
-        Ok(())
+        Ok(account_log)
+    }
+
+    // 生成批次id
+    pub async fn next_batch_id(executor: impl PgExecutor<'_>) -> AppResult<i64> {
+        let batch_id =
+            sqlx::query_scalar!(r#"select nextval('account_action_batch_id_seq') as "batch_id!""#)
+                .fetch_one(executor)
+                .await?;
+        Ok(batch_id)
+    }
+
+    // 客户端某批次的操作结果，按写入顺序排列
+    pub async fn find_batch(
+        executor: impl PgExecutor<'_>,
+        batch_id: i64,
+        client_id: i32,
+    ) -> AppResult<Vec<BatchActionResult>> {
+        let results = sqlx::query_as!(
+            BatchActionResult,
+            r#"select
+                l.id as log_id,
+                a.user_id,
+                a.asset_type_id,
+                l.action_type_id,
+                l.order_number,
+                l.available_balance_after as available_balance,
+                l.frozen_balance_after as frozen_balance,
+                l.total_income_after as total_income,
+                l.total_expense_after as total_expense
+            from
+                account_log l
+                join account a on a.id = l.account_id
+            where
+                l.batch_id = $1
+                and l.client_id = $2
+            order by
+                l.id"#,
+            batch_id,
+            client_id
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(results)
+    }
+
+    // 按订单号跨账户查询操作记录，指定时间范围时只扫描对应分区
+    pub async fn find_by_order_number(
+        executor: impl PgExecutor<'_>,
+        order_number: &str,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> AppResult<Vec<OrderLogModel>> {
+        let (start, end) = range.unzip();
+        let logs = sqlx::query_as!(
+            OrderLogModel,
+            r#"select
+                l.id,
+                a.user_id,
+                a.asset_type_id,
+                s.name as asset_type_name,
+                l.action_type_id,
+                t.name as action_type_name,
+                l.amount_available_balance,
+                l.amount_frozen_balance,
+                l.amount_total_income,
+                l.amount_total_expense,
+                l.available_balance_after,
+                l.frozen_balance_after,
+                l.total_income_after,
+                l.total_expense_after,
+                l.order_number,
+                l.description,
+                l.transfer_id,
+                l.reversed_log_id,
+                l.client_id,
+                l.batch_id,
+                l.created_at
+            from
+                account_log l
+                join account a on a.id = l.account_id
+                join asset_type s on s.id = a.asset_type_id
+                join action_type t on t.id = l.action_type_id
+            where
+                l.order_number = $1
+                and l.created_at >= coalesce($2, '-infinity'::timestamptz)
+                and l.created_at < coalesce($3, 'infinity'::timestamptz)
+            order by
+                l.created_at,
+                l.id"#,
+            order_number,
+            start,
+            end
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(logs)
+    }
+
+    // 按记录内容、写入时的哈希格式版本及给定的上一条记录哈希重新计算哈希
+    pub fn compute_hash(&self, prev_hash: Option<&str>) -> AppResult<String> {
+        ChainHashInput {
+            account_id: self.account_id,
+            action_type_id: self.action_type_id,
+            amount_available_balance: self.amount_available_balance,
+            amount_frozen_balance: self.amount_frozen_balance,
+            amount_total_income: self.amount_total_income,
+            amount_total_expense: self.amount_total_expense,
+            available_balance_after: self.available_balance_after,
+            frozen_balance_after: self.frozen_balance_after,
+            total_income_after: self.total_income_after,
+            total_expense_after: self.total_expense_after,
+            order_number: &self.order_number,
+            description: &self.description,
+            transfer_id: self.transfer_id,
+            reversed_log_id: self.reversed_log_id,
+            client_id: self.client_id,
+            batch_id: self.batch_id,
+            created_at: self.created_at,
+            prev_hash,
+        }
+        .hash(self.hash_version)
+    }
+
+    // 按写入顺序读取账户的下一批记录，用于校验哈希链
+    pub async fn fetch_chain(
+        executor: impl PgExecutor<'_>,
+        account_id: i32,
+        after_id: i64,
+        limit: i64,
+    ) -> AppResult<Vec<Self>> {
+        let account_logs = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                account_id,
+                action_type_id,
+                amount_available_balance,
+                amount_frozen_balance,
+                amount_total_income,
+                amount_total_expense,
+                available_balance_after,
+                frozen_balance_after,
+                total_income_after,
+                total_expense_after,
+                order_number,
+                description,
+                transfer_id,
+                reversed_log_id,
+                client_id,
+                batch_id,
+                created_at,
+                prev_hash,
+                hash,
+                hash_version
+            from
+                account_log
+            where
+                account_id = $1
+                and id > $2
+            order by
+                id
+            limit $3"#,
+            account_id,
+            after_id,
+            limit
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(account_logs)
     }
 
     // 账户操作日志是否存在
@@ -104,16 +480,107 @@ impl AccountLogModel {
         false
     }
 
-    pub async fn query_with_pagination(
+    // 指定订单号下可冲正的原日志（不含冲正日志本身），只能冲正本客户端写入的日志
+    pub async fn find_reversible(
         executor: impl PgExecutor<'_>,
         account_id: i32,
+        order_number: &str,
         action_type_id: Option<i32>,
-        start_time: Option<DateTime<Utc>>,
-        end_time: Option<DateTime<Utc>>,
-        page: i32,
-        page_size: i32,
+        client_id: i32,
+    ) -> AppResult<Vec<Self>> {
+        let account_logs = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                account_id,
+                action_type_id,
+                amount_available_balance,
+                amount_frozen_balance,
+                amount_total_income,
+                amount_total_expense,
+                available_balance_after,
+                frozen_balance_after,
+                total_income_after,
+                total_expense_after,
+                order_number,
+                description,
+                transfer_id,
+                reversed_log_id,
+                client_id,
+                batch_id,
+                created_at,
+                prev_hash,
+                hash,
+                hash_version
+            from
+                account_log
+            where
+                account_id = $1
+                and order_number = $2
+                and reversed_log_id is null
+                and ($3::int is null or action_type_id = $3)
+                and client_id = $4
+            order by
+                created_at desc,
+                id desc"#,
+            account_id,
+            order_number,
+            action_type_id,
+            client_id
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(account_logs)
+    }
+
+    // 原日志已冲正的累计金额
+    pub async fn reversed_amount(
+        executor: impl PgExecutor<'_>,
+        account_id: i32,
+        reversed_log_id: i64,
+    ) -> AppResult<Decimal> {
+        let amount = sqlx::query_scalar!(
+            r#"select
+                coalesce(sum(greatest(
+                    abs(amount_available_balance),
+                    abs(amount_frozen_balance),
+                    abs(amount_total_income),
+                    abs(amount_total_expense)
+                )), 0) as "amount!"
+            from
+                account_log
+            where
+                account_id = $1
+                and reversed_log_id = $2"#,
+            account_id,
+            reversed_log_id
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(amount)
+    }
+
+    // 操作金额（各金额字段绝对值中的最大值）
+    pub fn amount(&self) -> Decimal {
+        [
+            self.amount_available_balance,
+            self.amount_frozen_balance,
+            self.amount_total_income,
+            self.amount_total_expense,
+        ]
+        .into_iter()
+        .map(|amount| amount.abs())
+        .max()
+        .unwrap_or_default()
+    }
+
+    pub async fn query_with_pagination(
+        executor: impl PgExecutor<'_>,
+        filter: &AccountLogFilter,
+        cursor: Option<i64>,
+        offset: i64,
+        limit: i64,
     ) -> AppResult<Vec<Self>> {
-        let offset = (page - 1) * page_size;
         let mut query_builder = QueryBuilder::new(
             "select
                 id,
@@ -129,24 +596,86 @@ impl AccountLogModel {
                 total_expense_after,
                 order_number,
                 description,
-                created_at
-            from account_log where account_id = ",
+                transfer_id,
+                reversed_log_id,
+                client_id,
+                batch_id,
+                created_at,
+                prev_hash,
+                hash,
+                hash_version
+            from account_log where ",
         );
-        query_builder.push_bind(account_id);
-        if let Some(action_type) = action_type_id {
-            query_builder.push(" and action_type_id = ");
-            query_builder.push_bind(action_type);
+        filter.push_conditions(&mut query_builder);
+        // 按`id`定位：同一账户的记录在账户行锁内写入，`id`顺序即提交顺序，翻页期间写入新记录不会导致重复或遗漏
+        // 不能按`created_at`定位，它是事务开始时间，晚提交的事务可能落在已翻过的位置
+        if let Some(cursor_id) = cursor {
+            query_builder.push(" and id < ");
+            query_builder.push_bind(cursor_id);
         }
-        if let Some(start) = start_time {
-            query_builder.push(" and created_at >= ");
-            query_builder.push_bind(start);
-        }
-        if let Some(end) = end_time {
-            query_builder.push(" and created_at <= ");
-            query_builder.push_bind(end);
+        query_builder.push(" order by id desc limit ");
+        query_builder.push_bind(limit);
+        query_builder.push(" offset ");
+        query_builder.push_bind(offset);
+        let rows = query_builder
+            .build_query_as::<AccountLogModel>()
+            .fetch_all(executor)
+            .await?;
+        Ok(rows)
+    }
+
+    // 合并查询用户多个账户的操作记录，按`id`倒序分页
+    // 每个账户单独按`id`取前`offset + limit`条后再合并，各账户均可走索引
+    // 不同账户的写入互不加锁，`id`较大的记录可能先于较小的提交，翻页期间提交的记录可能落在游标之前而被跳过
+    pub async fn query_activity_with_pagination(
+        executor: impl PgExecutor<'_>,
+        filter: &AccountActivityFilter,
+        cursor: Option<i64>,
+        offset: i64,
+        limit: i64,
+    ) -> AppResult<Vec<Self>> {
+        let mut query_builder = QueryBuilder::new("select l.* from unnest(");
+        query_builder.push_bind(&filter.account_ids);
+        query_builder.push(
+            ") as a(account_id) cross join lateral (
+                select
+                    id,
+                    account_id,
+                    action_type_id,
+                    amount_available_balance,
+                    amount_frozen_balance,
+                    amount_total_income,
+                    amount_total_expense,
+                    available_balance_after,
+                    frozen_balance_after,
+                    total_income_after,
+                    total_expense_after,
+                    order_number,
+                    description,
+                    transfer_id,
+                    reversed_log_id,
+                    client_id,
+                    batch_id,
+                    created_at,
+                    prev_hash,
+                    hash,
+                    hash_version
+                from account_log where account_id = a.account_id",
+        );
+        push_range_conditions(
+            &mut query_builder,
+            filter.action_type_id,
+            filter.start_time,
+            filter.end_time,
+        );
+        if let Some(cursor_id) = cursor {
+            query_builder.push(" and id < ");
+            query_builder.push_bind(cursor_id);
         }
-        query_builder.push(" order by created_at desc limit ");
-        query_builder.push_bind(page_size);
+        query_builder.push(" order by id desc limit ");
+        query_builder.push_bind(offset + limit);
+        query_builder.push(") as l order by l.id desc limit ");
+        query_builder.push_bind(limit);
         query_builder.push(" offset ");
         query_builder.push_bind(offset);
         let rows = query_builder
@@ -155,4 +684,211 @@ impl AccountLogModel {
             .await?;
         Ok(rows)
     }
+
+    // 按查询条件统计记录总数及各金额变动合计
+    pub async fn summarize(
+        executor: impl PgExecutor<'_>,
+        filter: &AccountLogFilter,
+    ) -> AppResult<AccountLogSummary> {
+        let mut query_builder = QueryBuilder::new(
+            "select
+                count(*) as total,
+                coalesce(sum(amount_available_balance), 0) as amount_available_balance,
+                coalesce(sum(amount_frozen_balance), 0) as amount_frozen_balance,
+                coalesce(sum(amount_total_income), 0) as amount_total_income,
+                coalesce(sum(amount_total_expense), 0) as amount_total_expense
+            from account_log where ",
+        );
+        filter.push_conditions(&mut query_builder);
+        let summary = query_builder
+            .build_query_as::<AccountLogSummary>()
+            .fetch_one(executor)
+            .await?;
+        Ok(summary)
+    }
+
+    // 指定时间之前最后一条记录的变更后余额，即该时刻的账户余额
+    // 按`(created_at, id)`倒序取一条，可走游标索引
+    pub async fn balance_before(
+        executor: impl PgExecutor<'_>,
+        account_id: i32,
+        before: DateTime<Utc>,
+    ) -> AppResult<Option<AccountBalance>> {
+        let balance = sqlx::query_as!(
+            AccountBalance,
+            r#"select
+                available_balance_after as available_balance,
+                frozen_balance_after as frozen_balance,
+                total_income_after as total_income,
+                total_expense_after as total_expense
+            from
+                account_log
+            where
+                account_id = $1
+                and created_at < $2
+            order by
+                created_at desc,
+                id desc
+            limit 1"#,
+            account_id,
+            before
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(balance)
+    }
+
+    // 在事务内声明导出游标，按时间正序读取符合条件的全部记录
+    // 操作类型名称直接从`action_type`表读取，已停用的操作类型同样导出名称
+    pub async fn declare_export_cursor(
+        executor: impl PgExecutor<'_>,
+        filter: &AccountLogFilter,
+    ) -> AppResult<()> {
+        let mut query_builder = QueryBuilder::new(
+            "declare account_log_export no scroll cursor for select
+                created_at,
+                order_number,
+                action_type_id,
+                coalesce((select name from action_type where action_type.id = account_log.action_type_id), '') as action_type_name,
+                amount_available_balance,
+                amount_frozen_balance,
+                amount_total_income,
+                amount_total_expense,
+                available_balance_after,
+                frozen_balance_after,
+                total_income_after,
+                total_expense_after,
+                description,
+                transfer_id,
+                reversed_log_id,
+                client_id,
+                batch_id
+            from account_log where ",
+        );
+        filter.push_conditions(&mut query_builder);
+        query_builder.push(" order by created_at, id");
+        query_builder.build().execute(executor).await?;
+        Ok(())
+    }
+
+    // 从导出游标读取下一批记录，读取完毕时返回空
+    pub async fn fetch_export_cursor(
+        executor: impl PgExecutor<'_>,
+        size: i64,
+    ) -> AppResult<Vec<AccountLogExportRow>> {
+        let rows = sqlx::query_as::<_, AccountLogExportRow>(&format!(
+            "fetch forward {size} from account_log_export"
+        ))
+        .fetch_all(executor)
+        .await?;
+        Ok(rows)
+    }
+}
+
+// 参与哈希计算的记录内容
+// 金额去除末尾零、时间取微秒时间戳，保证与数据库读回的值计算结果一致
+// 版本1不含`batch_id`，按记录保存的版本计算，历史记录仍可校验
+struct ChainHashInput<'a> {
+    account_id: i32,
+    action_type_id: i32,
+    amount_available_balance: Decimal,
+    amount_frozen_balance: Decimal,
+    amount_total_income: Decimal,
+    amount_total_expense: Decimal,
+    available_balance_after: Decimal,
+    frozen_balance_after: Decimal,
+    total_income_after: Decimal,
+    total_expense_after: Decimal,
+    order_number: &'a str,
+    description: &'a str,
+    transfer_id: Option<i64>,
+    reversed_log_id: Option<i64>,
+    client_id: Option<i32>,
+    batch_id: Option<i64>,
+    created_at: DateTime<Utc>,
+    prev_hash: Option<&'a str>,
+}
+
+impl ChainHashInput<'_> {
+    fn hash(&self, version: i16) -> AppResult<String> {
+        let mut fields = serde_json::json!([
+            self.account_id,
+            self.action_type_id,
+            self.amount_available_balance.normalize().to_string(),
+            self.amount_frozen_balance.normalize().to_string(),
+            self.amount_total_income.normalize().to_string(),
+            self.amount_total_expense.normalize().to_string(),
+            self.available_balance_after.normalize().to_string(),
+            self.frozen_balance_after.normalize().to_string(),
+            self.total_income_after.normalize().to_string(),
+            self.total_expense_after.normalize().to_string(),
+            self.order_number,
+            self.description,
+            self.transfer_id,
+            self.reversed_log_id,
+            self.client_id,
+            self.created_at.timestamp_micros(),
+            self.prev_hash.unwrap_or_default(),
+        ]);
+        match version {
+            1 => {}
+            2 => {
+                if let Some(fields) = fields.as_array_mut() {
+                    fields.push(serde_json::json!(self.batch_id));
+                }
+            }
+            _ => return Err(anyhow::anyhow!("不支持的哈希格式版本{}", version))?,
+        }
+        let canonical = serde_json::to_string(&fields).map_err(anyhow::Error::from)?;
+        Ok(utils::sha256_hex(canonical))
+    }
+}
+
+// 账户操作记录查询条件
+pub struct AccountLogFilter {
+    pub account_id: i32,
+    pub action_type_id: Option<i32>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl AccountLogFilter {
+    fn push_conditions(&self, query_builder: &mut QueryBuilder<'_, Postgres>) {
+        query_builder.push("account_id = ");
+        query_builder.push_bind(self.account_id);
+        push_range_conditions(
+            query_builder,
+            self.action_type_id,
+            self.start_time,
+            self.end_time,
+        );
+    }
+}
+
+// 用户多个账户的操作记录查询条件
+pub struct AccountActivityFilter {
+    pub account_ids: Vec<i32>,
+    pub action_type_id: Option<i32>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+fn push_range_conditions(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    action_type_id: Option<i32>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+) {
+    if let Some(action_type_id) = action_type_id {
+        query_builder.push(" and action_type_id = ");
+        query_builder.push_bind(action_type_id);
+    }
+    if let Some(start_time) = start_time {
+        query_builder.push(" and created_at >= ");
+        query_builder.push_bind(start_time);
+    }
+    if let Some(end_time) = end_time {
+        query_builder.push(" and created_at <= ");
+        query_builder.push_bind(end_time);
+    }
 }
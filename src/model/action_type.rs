@@ -1,5 +1,5 @@
 use axum_kit::AppResult;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::{
     PgExecutor,
     types::{
@@ -8,8 +8,9 @@ use sqlx::{
     },
 };
 
-#[derive(Serialize, sqlx::Type, PartialEq)]
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Clone, Debug)]
 #[sqlx(type_name = "change_enum", rename_all = "UPPERCASE")]
+#[serde(rename_all = "UPPERCASE")]
 pub enum Change {
     Inc,
     Dec,
@@ -25,9 +26,20 @@ impl Change {
             Change::None => Decimal::ZERO,
         }
     }
+
+    // 抵消已发生的金额变化所需的变化方向
+    pub fn reverse_of(amount: Decimal) -> Self {
+        if amount.is_zero() {
+            Change::None
+        } else if amount.is_sign_positive() {
+            Change::Dec
+        } else {
+            Change::Inc
+        }
+    }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ActionTypeModel {
     pub id: i32,
     pub name: String,
@@ -48,6 +60,7 @@ pub struct ActionTypeModel {
 }
 
 impl ActionTypeModel {
+    // 全部操作类型，包括已停用的
     pub async fn fetch_all(executor: impl PgExecutor<'_>) -> AppResult<Vec<Self>> {
         let action_types: Vec<Self> = sqlx::query_as!(
             Self,
@@ -64,11 +77,179 @@ impl ActionTypeModel {
                 updated_at
             from
                 action_type
-            where
-                is_active = true"#
+            order by
+                id"#
         )
         .fetch_all(executor)
         .await?;
         Ok(action_types)
     }
+
+    pub async fn find(executor: impl PgExecutor<'_>, id: i32) -> AppResult<Option<Self>> {
+        let action_type = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                name,
+                description,
+                available_balance_change as "available_balance_change!: Change",
+                frozen_balance_change as "frozen_balance_change!: Change",
+                total_income_change as "total_income_change!: Change",
+                total_expense_change as "total_expense_change!: Change",
+                is_active,
+                created_at,
+                updated_at
+            from
+                action_type
+            where
+                id = $1"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(action_type)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        name: &str,
+        description: &str,
+        available_balance_change: Change,
+        frozen_balance_change: Change,
+        total_income_change: Change,
+        total_expense_change: Change,
+        is_active: bool,
+    ) -> AppResult<Self> {
+        let action_type = sqlx::query_as!(
+            Self,
+            r#"insert into action_type(
+                name,
+                description,
+                available_balance_change,
+                frozen_balance_change,
+                total_income_change,
+                total_expense_change,
+                is_active
+            )
+            values ($1, $2, $3, $4, $5, $6, $7)
+            returning
+                id,
+                name,
+                description,
+                available_balance_change as "available_balance_change!: Change",
+                frozen_balance_change as "frozen_balance_change!: Change",
+                total_income_change as "total_income_change!: Change",
+                total_expense_change as "total_expense_change!: Change",
+                is_active,
+                created_at,
+                updated_at"#,
+            name,
+            description,
+            available_balance_change as Change,
+            frozen_balance_change as Change,
+            total_income_change as Change,
+            total_expense_change as Change,
+            is_active
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(action_type)
+    }
+
+    // 仅更新传入的字段
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        name: Option<&str>,
+        description: Option<&str>,
+        available_balance_change: Option<Change>,
+        frozen_balance_change: Option<Change>,
+        total_income_change: Option<Change>,
+        total_expense_change: Option<Change>,
+    ) -> AppResult<Self> {
+        let action_type = sqlx::query_as!(
+            Self,
+            r#"update action_type
+                set name = coalesce($2, name),
+                description = coalesce($3, description),
+                available_balance_change = coalesce($4, available_balance_change),
+                frozen_balance_change = coalesce($5, frozen_balance_change),
+                total_income_change = coalesce($6, total_income_change),
+                total_expense_change = coalesce($7, total_expense_change)
+            where
+                id = $1
+            returning
+                id,
+                name,
+                description,
+                available_balance_change as "available_balance_change!: Change",
+                frozen_balance_change as "frozen_balance_change!: Change",
+                total_income_change as "total_income_change!: Change",
+                total_expense_change as "total_expense_change!: Change",
+                is_active,
+                created_at,
+                updated_at"#,
+            id,
+            name,
+            description,
+            available_balance_change as Option<Change>,
+            frozen_balance_change as Option<Change>,
+            total_income_change as Option<Change>,
+            total_expense_change as Option<Change>
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(action_type)
+    }
+
+    pub async fn set_active(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        is_active: bool,
+    ) -> AppResult<Self> {
+        let action_type = sqlx::query_as!(
+            Self,
+            r#"update action_type
+                set is_active = $2
+            where
+                id = $1
+            returning
+                id,
+                name,
+                description,
+                available_balance_change as "available_balance_change!: Change",
+                frozen_balance_change as "frozen_balance_change!: Change",
+                total_income_change as "total_income_change!: Change",
+                total_expense_change as "total_expense_change!: Change",
+                is_active,
+                created_at,
+                updated_at"#,
+            id,
+            is_active
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(action_type)
+    }
+
+    // 操作类型名称是否已被其他记录使用
+    pub async fn is_name_exists(
+        executor: impl PgExecutor<'_>,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> bool {
+        if let Ok(Some(exists)) = sqlx::query_scalar!(
+            r#"select exists(select 1 from action_type where name = $1 and id is distinct from $2)"#,
+            name,
+            exclude_id
+        )
+        .fetch_one(executor)
+        .await
+        {
+            return exists;
+        }
+        false
+    }
 }
@@ -0,0 +1,99 @@
+use axum_kit::AppResult;
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Utc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// 归档清单，与归档文件保存在同一目录
+#[derive(Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub partition: String,
+    pub range_start: DateTime<Utc>,
+    pub range_end: DateTime<Utc>,
+    pub rows: i64,
+    // 归档文件名（gzip压缩的CSV，含表头）
+    pub file: String,
+    // 归档文件SHA-256摘要
+    pub sha256: String,
+    pub columns: Vec<String>,
+    pub archived_at: DateTime<Utc>,
+}
+
+impl ArchiveManifest {
+    pub fn load(dir: &Path, partition: &str) -> AppResult<Option<Self>> {
+        Self::read(&Self::path(dir, partition))
+    }
+
+    // 分区删除前先保存为待确认清单，删除提交后再确认
+    pub fn save_pending(&self, dir: &Path) -> AppResult<()> {
+        let content = serde_json::to_vec_pretty(self).map_err(anyhow::Error::from)?;
+        fs::write(Self::pending_path(dir, &self.partition), content)
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    // 确认待确认清单，确认后分区视为已归档
+    pub fn confirm(dir: &Path, partition: &str) -> AppResult<Self> {
+        let manifest = Self::read(&Self::pending_path(dir, partition))?
+            .ok_or_else(|| anyhow::anyhow!("待确认的归档清单不存在: {}", partition))?;
+        fs::rename(
+            Self::pending_path(dir, partition),
+            Self::path(dir, partition),
+        )
+        .map_err(anyhow::Error::from)?;
+        Ok(manifest)
+    }
+
+    // 存在待确认清单的分区
+    pub fn pending(dir: &Path) -> AppResult<Vec<String>> {
+        Self::partitions(dir, ".json.pending")
+    }
+
+    pub fn list(dir: &Path) -> AppResult<Vec<Self>> {
+        let mut manifests = Vec::new();
+        for partition in Self::partitions(dir, ".json")? {
+            if let Some(manifest) = Self::load(dir, &partition)? {
+                manifests.push(manifest);
+            }
+        }
+        manifests.sort_by(|a, b| a.partition.cmp(&b.partition));
+        Ok(manifests)
+    }
+
+    fn read(path: &Path) -> AppResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read(path).map_err(anyhow::Error::from)?;
+        let manifest = serde_json::from_slice(&content).map_err(anyhow::Error::from)?;
+        Ok(Some(manifest))
+    }
+
+    fn partitions(dir: &Path, suffix: &str) -> AppResult<Vec<String>> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut partitions = Vec::new();
+        for entry in fs::read_dir(dir).map_err(anyhow::Error::from)? {
+            let path = entry.map_err(anyhow::Error::from)?.path();
+            if let Some(partition) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(suffix))
+            {
+                partitions.push(partition.to_string());
+            }
+        }
+        Ok(partitions)
+    }
+
+    fn path(dir: &Path, partition: &str) -> PathBuf {
+        dir.join(format!("{partition}.json"))
+    }
+
+    fn pending_path(dir: &Path, partition: &str) -> PathBuf {
+        dir.join(format!("{partition}.json.pending"))
+    }
+}
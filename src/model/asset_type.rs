@@ -5,7 +5,7 @@ use sqlx::{
     types::chrono::{DateTime, Utc},
 };
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct AssetTypeModel {
     pub id: i32,
     pub name: String,
@@ -41,4 +41,126 @@ impl AssetTypeModel {
         .await?;
         Ok(asset_types)
     }
+
+    pub async fn find(executor: impl PgExecutor<'_>, id: i32) -> AppResult<Option<Self>> {
+        let asset_type = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                name,
+                description,
+                is_active,
+                created_at,
+                updated_at
+            from
+                asset_type
+            where
+                id = $1"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(asset_type)
+    }
+
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        name: &str,
+        description: &str,
+        is_active: bool,
+    ) -> AppResult<Self> {
+        let asset_type = sqlx::query_as!(
+            Self,
+            r#"insert into asset_type(name, description, is_active)
+                values ($1, $2, $3)
+            returning
+                id,
+                name,
+                description,
+                is_active,
+                created_at,
+                updated_at"#,
+            name,
+            description,
+            is_active
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(asset_type)
+    }
+
+    // 仅更新传入的字段
+    pub async fn update(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) -> AppResult<Self> {
+        let asset_type = sqlx::query_as!(
+            Self,
+            r#"update asset_type
+                set name = coalesce($2, name),
+                description = coalesce($3, description)
+            where
+                id = $1
+            returning
+                id,
+                name,
+                description,
+                is_active,
+                created_at,
+                updated_at"#,
+            id,
+            name,
+            description
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(asset_type)
+    }
+
+    pub async fn set_active(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        is_active: bool,
+    ) -> AppResult<Self> {
+        let asset_type = sqlx::query_as!(
+            Self,
+            r#"update asset_type
+                set is_active = $2
+            where
+                id = $1
+            returning
+                id,
+                name,
+                description,
+                is_active,
+                created_at,
+                updated_at"#,
+            id,
+            is_active
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(asset_type)
+    }
+
+    // 资产类型名称是否已被其他记录使用
+    pub async fn is_name_exists(
+        executor: impl PgExecutor<'_>,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> bool {
+        if let Ok(Some(exists)) = sqlx::query_scalar!(
+            r#"select exists(select 1 from asset_type where name = $1 and id is distinct from $2)"#,
+            name,
+            exclude_id
+        )
+        .fetch_one(executor)
+        .await
+        {
+            return exists;
+        }
+        false
+    }
 }
@@ -0,0 +1,236 @@
+use axum_kit::AppResult;
+use serde::Serialize;
+use sqlx::{
+    PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+
+#[derive(Serialize, Clone)]
+pub struct ClientModel {
+    pub id: i32,
+    pub name: String,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub api_key_hash: String,
+    pub asset_type_ids: Vec<i32>,
+    pub action_type_ids: Vec<i32>,
+    pub is_active: bool,
+    #[serde(skip_serializing)]
+    pub signing_secret: Option<String>,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub created_at: DateTime<Utc>,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ClientModel {
+    // 是否允许使用指定资产类型和操作类型
+    pub fn is_allowed(&self, asset_type_id: i32, action_type_id: i32) -> bool {
+        self.is_asset_allowed(asset_type_id) && self.action_type_ids.contains(&action_type_id)
+    }
+
+    // 是否允许使用指定资产类型
+    pub fn is_asset_allowed(&self, asset_type_id: i32) -> bool {
+        self.asset_type_ids.contains(&asset_type_id)
+    }
+
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        name: &str,
+        api_key_hash: &str,
+        asset_type_ids: &[i32],
+        action_type_ids: &[i32],
+        signing_secret: Option<&str>,
+    ) -> AppResult<Self> {
+        let client = sqlx::query_as!(
+            Self,
+            r#"insert into client(name, api_key_hash, asset_type_ids, action_type_ids, signing_secret, is_active)
+                values ($1, $2, $3, $4, $5, true)
+            returning
+                id,
+                name,
+                api_key_hash,
+                asset_type_ids,
+                action_type_ids,
+                is_active,
+                signing_secret,
+                created_at,
+                updated_at"#,
+            name,
+            api_key_hash,
+            asset_type_ids,
+            action_type_ids,
+            signing_secret
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(client)
+    }
+
+    pub async fn find_by_api_key_hash(
+        executor: impl PgExecutor<'_>,
+        api_key_hash: &str,
+    ) -> AppResult<Option<Self>> {
+        let client = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                name,
+                api_key_hash,
+                asset_type_ids,
+                action_type_ids,
+                is_active,
+                signing_secret,
+                created_at,
+                updated_at
+            from
+                client
+            where
+                api_key_hash = $1"#,
+            api_key_hash
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(client)
+    }
+
+    // 仅更新传入的字段
+    pub async fn update(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        asset_type_ids: Option<&[i32]>,
+        action_type_ids: Option<&[i32]>,
+        is_active: Option<bool>,
+    ) -> AppResult<Option<Self>> {
+        let client = sqlx::query_as!(
+            Self,
+            r#"update client
+                set asset_type_ids = coalesce($2, asset_type_ids),
+                action_type_ids = coalesce($3, action_type_ids),
+                is_active = coalesce($4, is_active)
+            where
+                id = $1
+            returning
+                id,
+                name,
+                api_key_hash,
+                asset_type_ids,
+                action_type_ids,
+                is_active,
+                signing_secret,
+                created_at,
+                updated_at"#,
+            id,
+            asset_type_ids,
+            action_type_ids,
+            is_active
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(client)
+    }
+
+    // 设置或清除请求签名密钥
+    pub async fn set_signing_secret(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        signing_secret: Option<&str>,
+    ) -> AppResult<Option<Self>> {
+        let client = sqlx::query_as!(
+            Self,
+            r#"update client
+                set signing_secret = $2
+            where
+                id = $1
+            returning
+                id,
+                name,
+                api_key_hash,
+                asset_type_ids,
+                action_type_ids,
+                is_active,
+                signing_secret,
+                created_at,
+                updated_at"#,
+            id,
+            signing_secret
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(client)
+    }
+
+    // 尚未加密的签名密钥
+    pub async fn plaintext_signing_secrets(
+        executor: impl PgExecutor<'_>,
+        encrypted_prefix: &str,
+    ) -> AppResult<Vec<(i32, String)>> {
+        let rows = sqlx::query!(
+            r#"select
+                id,
+                signing_secret as "signing_secret!"
+            from
+                client
+            where
+                signing_secret is not null
+                and not starts_with(signing_secret, $1)"#,
+            encrypted_prefix
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.signing_secret))
+            .collect())
+    }
+
+    // 将明文签名密钥替换为密文，密钥已被修改时不更新
+    pub async fn encrypt_signing_secret(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        plaintext: &str,
+        encrypted: &str,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            r#"update client
+                set signing_secret = $3
+            where
+                id = $1
+                and signing_secret = $2"#,
+            id,
+            plaintext,
+            encrypted
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 客户端是否存在
+    pub async fn is_exists_by_id(executor: impl PgExecutor<'_>, id: i32) -> bool {
+        if let Ok(Some(exists)) =
+            sqlx::query_scalar!(r#"select exists(select 1 from client where id = $1)"#, id)
+                .fetch_one(executor)
+                .await
+        {
+            return exists;
+        }
+        false
+    }
+
+    // 客户端名称是否存在
+    pub async fn is_exists(executor: impl PgExecutor<'_>, name: &str) -> bool {
+        if let Ok(Some(exists)) = sqlx::query_scalar!(
+            r#"select exists(select 1 from client where name = $1)"#,
+            name
+        )
+        .fetch_one(executor)
+        .await
+        {
+            return exists;
+        }
+        false
+    }
+}
@@ -0,0 +1,261 @@
+use super::serialize_utc_to_session_tz;
+use axum_kit::AppResult;
+use serde::Serialize;
+use sqlx::{
+    PgExecutor,
+    types::{
+        Decimal,
+        chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(Serialize, sqlx::Type, PartialEq, Debug)]
+#[sqlx(type_name = "hold_status_enum", rename_all = "UPPERCASE")]
+pub enum HoldStatus {
+    Active,
+    Captured,
+    Voided,
+    Expired,
+}
+
+#[derive(Serialize)]
+pub struct HoldModel {
+    pub id: i64,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    // 发起预授权的客户端，只有该客户端可以扣款、撤销及查询
+    #[serde(skip_serializing)]
+    pub client_id: Option<i32>,
+    pub amount: Decimal,
+    pub captured_amount: Decimal,
+    pub released_amount: Decimal,
+    pub status: HoldStatus,
+    pub order_number: String,
+    pub description: String,
+    #[serde(serialize_with = "serialize_utc_to_session_tz")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_utc_to_session_tz")]
+    pub created_at: DateTime<Utc>,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl HoldModel {
+    // 剩余冻结中的金额
+    pub fn remaining_amount(&self) -> Decimal {
+        self.amount - self.captured_amount - self.released_amount
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        user_id: i32,
+        asset_type_id: i32,
+        client_id: i32,
+        amount: Decimal,
+        order_number: &str,
+        description: &str,
+        expires_in: i64,
+    ) -> AppResult<Self> {
+        let hold = sqlx::query_as!(
+            Self,
+            r#"insert into hold(user_id, asset_type_id, client_id, amount, order_number, description, expires_at)
+                values ($1, $2, $3, $4, $5, $6, now() + make_interval(secs => $7))
+            returning
+                id,
+                user_id,
+                asset_type_id,
+                client_id,
+                amount,
+                captured_amount,
+                released_amount,
+                status as "status!: HoldStatus",
+                order_number,
+                description,
+                expires_at,
+                created_at,
+                updated_at"#,
+            user_id,
+            asset_type_id,
+            client_id,
+            amount,
+            order_number,
+            description,
+            expires_in as f64
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(hold)
+    }
+
+    pub async fn find(executor: impl PgExecutor<'_>, id: i64) -> AppResult<Option<Self>> {
+        let hold = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                user_id,
+                asset_type_id,
+                client_id,
+                amount,
+                captured_amount,
+                released_amount,
+                status as "status!: HoldStatus",
+                order_number,
+                description,
+                expires_at,
+                created_at,
+                updated_at
+            from
+                hold
+            where
+                id = $1"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(hold)
+    }
+
+    // 事务内查询并锁定预授权（行级锁，仅在事务内生效）
+    pub async fn find_for_update(
+        executor: impl PgExecutor<'_>,
+        id: i64,
+    ) -> AppResult<Option<Self>> {
+        let hold = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                user_id,
+                asset_type_id,
+                client_id,
+                amount,
+                captured_amount,
+                released_amount,
+                status as "status!: HoldStatus",
+                order_number,
+                description,
+                expires_at,
+                created_at,
+                updated_at
+            from
+                hold
+            where
+                id = $1
+            for update"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(hold)
+    }
+
+    // 预授权订单是否存在
+    pub async fn is_exists(executor: impl PgExecutor<'_>, order_number: &str) -> bool {
+        if let Ok(Some(exists)) = sqlx::query_scalar!(
+            r#"select exists(select 1 from hold where order_number = $1)"#,
+            order_number
+        )
+        .fetch_one(executor)
+        .await
+        {
+            return exists;
+        }
+        false
+    }
+
+    // 已过期但仍处于冻结中的预授权
+    pub async fn expired_ids(executor: impl PgExecutor<'_>, limit: i64) -> AppResult<Vec<i64>> {
+        let ids = sqlx::query_scalar!(
+            r#"select
+                id
+            from
+                hold
+            where
+                status = 'ACTIVE'
+                and expires_at <= now()
+            order by
+                expires_at
+            limit $1"#,
+            limit
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(ids)
+    }
+
+    // 扣款，全部扣完时预授权结束
+    pub async fn capture(
+        executor: impl PgExecutor<'_>,
+        id: i64,
+        amount: Decimal,
+    ) -> AppResult<Self> {
+        let hold = sqlx::query_as!(
+            Self,
+            r#"update hold
+                set captured_amount = captured_amount + $2,
+                status = case when amount - captured_amount - released_amount - $2 = 0
+                    then 'CAPTURED'::hold_status_enum
+                    else status
+                end
+            where
+                id = $1
+            returning
+                id,
+                user_id,
+                asset_type_id,
+                client_id,
+                amount,
+                captured_amount,
+                released_amount,
+                status as "status!: HoldStatus",
+                order_number,
+                description,
+                expires_at,
+                created_at,
+                updated_at"#,
+            id,
+            amount
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(hold)
+    }
+
+    // 释放剩余冻结金额并结束预授权
+    pub async fn release(
+        executor: impl PgExecutor<'_>,
+        id: i64,
+        amount: Decimal,
+        status: HoldStatus,
+    ) -> AppResult<Self> {
+        let hold = sqlx::query_as!(
+            Self,
+            r#"update hold
+                set released_amount = released_amount + $2,
+                status = $3
+            where
+                id = $1
+            returning
+                id,
+                user_id,
+                asset_type_id,
+                client_id,
+                amount,
+                captured_amount,
+                released_amount,
+                status as "status!: HoldStatus",
+                order_number,
+                description,
+                expires_at,
+                created_at,
+                updated_at"#,
+            id,
+            amount,
+            status as HoldStatus
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(hold)
+    }
+}
@@ -0,0 +1,68 @@
+use axum_kit::AppResult;
+use sqlx::{
+    PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+
+pub struct IdempotencyKeyModel {
+    #[allow(dead_code)]
+    pub client_id: i32,
+    #[allow(dead_code)]
+    pub order_number: String,
+    pub request_hash: String,
+    // 迁移前写入的幂等键为空
+    pub batch_id: Option<i64>,
+    #[allow(dead_code)]
+    pub created_at: DateTime<Utc>,
+}
+
+impl IdempotencyKeyModel {
+    // 写入幂等键，已存在时返回`false`
+    // 并发写入同一幂等键时，后写入方会等待先写入方事务结束
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        client_id: i32,
+        order_number: &str,
+        request_hash: &str,
+        batch_id: i64,
+    ) -> AppResult<bool> {
+        let result = sqlx::query!(
+            r#"insert into idempotency_key(client_id, order_number, request_hash, batch_id)
+                values ($1, $2, $3, $4)
+            on conflict do nothing"#,
+            client_id,
+            order_number,
+            request_hash,
+            batch_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn find(
+        executor: impl PgExecutor<'_>,
+        client_id: i32,
+        order_number: &str,
+    ) -> AppResult<Self> {
+        let idempotency_key = sqlx::query_as!(
+            Self,
+            r#"select
+                client_id,
+                order_number,
+                request_hash,
+                batch_id,
+                created_at
+            from
+                idempotency_key
+            where
+                client_id = $1
+                and order_number = $2"#,
+            client_id,
+            order_number
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(idempotency_key)
+    }
+}
@@ -1,12 +1,23 @@
 pub mod account;
 pub mod account_log;
 pub mod action_type;
+pub mod archive;
 pub mod asset_type;
+pub mod client;
+pub mod hold;
+pub mod idempotency_key;
+pub mod outbox;
+pub mod partition;
+pub mod reconciliation;
+pub mod request_nonce;
+pub mod transfer;
+pub mod webhook;
 
-use axum_kit::postgres;
+use axum_kit::{AppResult, postgres};
 use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use serde::Serializer;
+use sqlx::PgExecutor;
 
 fn serialize_utc_to_session_tz<S>(
     utc_time: &DateTime<Utc>,
@@ -24,3 +35,51 @@ where
     let local_time = utc_time.with_timezone(&tz);
     serializer.serialize_str(&local_time.to_rfc3339())
 }
+
+// 事务级咨询锁，未取得时说明其他实例正在执行，返回`false`
+pub async fn try_advisory_xact_lock(
+    executor: impl PgExecutor<'_>,
+    lock_name: &str,
+) -> AppResult<bool> {
+    let locked = sqlx::query_scalar!(
+        r#"select pg_try_advisory_xact_lock(hashtext($1)) as "locked!""#,
+        lock_name
+    )
+    .fetch_one(executor)
+    .await?;
+    Ok(locked)
+}
+
+// 会话级咨询锁，用于期间不能持有事务的任务，须通过`advisory_unlock`释放
+pub async fn try_advisory_lock(executor: impl PgExecutor<'_>, lock_name: &str) -> AppResult<bool> {
+    let locked = sqlx::query_scalar!(
+        r#"select pg_try_advisory_lock(hashtext($1)) as "locked!""#,
+        lock_name
+    )
+    .fetch_one(executor)
+    .await?;
+    Ok(locked)
+}
+
+pub async fn advisory_unlock(executor: impl PgExecutor<'_>, lock_name: &str) -> AppResult<()> {
+    sqlx::query!(r#"select pg_advisory_unlock(hashtext($1))"#, lock_name)
+        .fetch_one(executor)
+        .await?;
+    Ok(())
+}
+
+// 当前事务改为只读的可重复读，事务内的查询读取同一快照
+pub async fn set_repeatable_read(executor: impl PgExecutor<'_>) -> AppResult<()> {
+    sqlx::query!(r#"set transaction isolation level repeatable read, read only"#)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+// 检查数据库连接
+pub async fn ping(executor: impl PgExecutor<'_>) -> AppResult<()> {
+    sqlx::query!(r#"select 1 as "one!""#)
+        .fetch_one(executor)
+        .await?;
+    Ok(())
+}
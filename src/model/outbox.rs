@@ -0,0 +1,205 @@
+use super::account_log::AccountLogModel;
+use crate::request::AccountActionRequest;
+use axum_kit::AppResult;
+use serde::Serialize;
+use sqlx::{
+    PgExecutor,
+    types::{
+        Decimal, JsonValue,
+        chrono::{DateTime, Utc},
+    },
+};
+
+// 事件类型
+#[derive(Clone, Copy)]
+pub enum EventType {
+    AccountActions,
+    AccountTransfer,
+    AccountReverse,
+    HoldAuthorize,
+    HoldCapture,
+    HoldVoid,
+    HoldExpire,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AccountActions => "account.actions",
+            Self::AccountTransfer => "account.transfer",
+            Self::AccountReverse => "account.reverse",
+            Self::HoldAuthorize => "hold.authorize",
+            Self::HoldCapture => "hold.capture",
+            Self::HoldVoid => "hold.void",
+            Self::HoldExpire => "hold.expire",
+        }
+    }
+}
+
+// 单个账户的余额变更
+#[derive(Serialize)]
+pub struct BalanceChange {
+    pub log_id: i64,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    pub action_type_id: i32,
+    // 操作金额
+    pub amount: Decimal,
+    pub amount_available_balance: Decimal,
+    pub amount_frozen_balance: Decimal,
+    pub amount_total_income: Decimal,
+    pub amount_total_expense: Decimal,
+    pub available_balance_after: Decimal,
+    pub frozen_balance_after: Decimal,
+    pub total_income_after: Decimal,
+    pub total_expense_after: Decimal,
+    pub order_number: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BalanceChange {
+    pub fn new(
+        account_action_request: &AccountActionRequest,
+        account_log: &AccountLogModel,
+    ) -> Self {
+        Self {
+            log_id: account_log.id,
+            user_id: account_action_request.user_id,
+            asset_type_id: account_action_request.asset_type_id,
+            action_type_id: account_log.action_type_id,
+            amount: account_action_request.amount.abs().trunc_with_scale(6),
+            amount_available_balance: account_log.amount_available_balance,
+            amount_frozen_balance: account_log.amount_frozen_balance,
+            amount_total_income: account_log.amount_total_income,
+            amount_total_expense: account_log.amount_total_expense,
+            available_balance_after: account_log.available_balance_after,
+            frozen_balance_after: account_log.frozen_balance_after,
+            total_income_after: account_log.total_income_after,
+            total_expense_after: account_log.total_expense_after,
+            order_number: account_log.order_number.clone(),
+            created_at: account_log.created_at,
+        }
+    }
+}
+
+// 余额变更事件内容，一次操作的全部变更
+#[derive(Serialize)]
+pub struct BalanceChangeEvent<'a> {
+    // 发起操作的客户端，系统自动处理时为空
+    pub client_id: Option<i32>,
+    pub changes: &'a [BalanceChange],
+}
+
+#[derive(Serialize)]
+pub struct OutboxEventModel {
+    pub id: i64,
+    // 发布序号，即发布顺序
+    pub publish_seq: i64,
+    pub event_type: String,
+    pub payload: JsonValue,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboxEventModel {
+    // 与余额变更在同一事务内写入
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        event_type: EventType,
+        event: &BalanceChangeEvent<'_>,
+    ) -> AppResult<()> {
+        let payload = serde_json::to_value(event).map_err(anyhow::Error::from)?;
+        sqlx::query!(
+            r#"insert into outbox_event(event_type, payload)
+                values ($1, $2)"#,
+            event_type.as_str(),
+            payload
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 按id顺序为已提交且未分配序号的事件分配发布序号，返回分配的数量
+    // 之后提交的事件在下次分配，序号更大；同一账户的事件在账户行锁内写入，id顺序即提交顺序
+    // `nextval`在排序后计算，保证序号按id递增
+    pub async fn assign_publish_seq(executor: impl PgExecutor<'_>) -> AppResult<u64> {
+        let result = sqlx::query!(
+            r#"update outbox_event e
+                set publish_seq = s.publish_seq
+            from
+                (
+                    select
+                        id,
+                        nextval('outbox_event_publish_seq') as publish_seq
+                    from
+                        outbox_event
+                    where
+                        publish_seq is null
+                    order by
+                        id
+                ) s
+            where
+                e.id = s.id"#
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    // 按发布序号读取未发布的事件
+    pub async fn fetch_unpublished(
+        executor: impl PgExecutor<'_>,
+        limit: i64,
+    ) -> AppResult<Vec<Self>> {
+        let events = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                publish_seq as "publish_seq!",
+                event_type,
+                payload,
+                created_at
+            from
+                outbox_event
+            where
+                published_at is null
+                and publish_seq is not null
+            order by
+                publish_seq
+            limit $1"#,
+            limit
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(events)
+    }
+
+    pub async fn mark_published(executor: impl PgExecutor<'_>, id: i64) -> AppResult<()> {
+        sqlx::query!(
+            r#"update outbox_event
+                set published_at = now()
+            where
+                id = $1"#,
+            id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 删除超出保留期的已发布事件
+    pub async fn delete_published(
+        executor: impl PgExecutor<'_>,
+        retention_days: i32,
+    ) -> AppResult<u64> {
+        let result = sqlx::query!(
+            r#"delete from outbox_event
+            where
+                published_at < now() - make_interval(days => $1)"#,
+            retention_days
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
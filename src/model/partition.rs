@@ -0,0 +1,194 @@
+use axum_kit::AppResult;
+use serde::Serialize;
+use sqlx::{
+    PgConnection, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use std::io;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+#[derive(Serialize)]
+pub struct PartitionModel {
+    pub name: String,
+    // 分区范围
+    pub bound: String,
+    // 估算行数，未分析过的分区为-1
+    pub estimated_rows: i64,
+    // 占用空间（字节）
+    pub total_bytes: i64,
+}
+
+impl PartitionModel {
+    // `account_log`的全部分区
+    pub async fn fetch_all(executor: impl PgExecutor<'_>) -> AppResult<Vec<Self>> {
+        let partitions = sqlx::query_as!(
+            Self,
+            r#"select
+                c.relname::text as "name!",
+                pg_get_expr(c.relpartbound, c.oid) as "bound!",
+                c.reltuples::bigint as "estimated_rows!",
+                pg_total_relation_size(c.oid) as "total_bytes!"
+            from
+                pg_inherits i
+                join pg_class c on c.oid = i.inhrelid
+            where
+                i.inhparent = 'account_log'::regclass
+            order by
+                c.relname"#
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(partitions)
+    }
+
+    // 创建当前月份及未来数月的分区，已存在的分区会跳过
+    pub async fn ensure(conn: &mut PgConnection, months_ahead: i32) -> AppResult<()> {
+        sqlx::query!(r#"select create_account_log_partition()"#)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query!(r#"select create_future_partitions($1)"#, months_ahead)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    // 以下操作的分区名均由服务按`account_log_YYYYMM`生成，不接受外部输入
+
+    pub async fn count_rows(executor: impl PgExecutor<'_>, name: &str) -> AppResult<i64> {
+        let rows = sqlx::query_scalar::<_, i64>(&format!(r#"select count(*) from "{name}""#))
+            .fetch_one(executor)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn columns(executor: impl PgExecutor<'_>, name: &str) -> AppResult<Vec<String>> {
+        let columns = sqlx::query_scalar!(
+            r#"select
+                column_name as "column_name!"
+            from
+                information_schema.columns
+            where
+                table_schema = 'public'
+                and table_name = $1
+            order by
+                ordinal_position"#,
+            name
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(columns)
+    }
+
+    // 阻止导出期间写入分区
+    pub async fn lock_for_export(executor: impl PgExecutor<'_>, name: &str) -> AppResult<()> {
+        sqlx::query(&format!(r#"lock table "{name}" in share mode"#))
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    // 以带表头的CSV格式导出分区全部记录，按数据块发送给写入文件的线程
+    pub async fn copy_out(
+        conn: &mut PgConnection,
+        name: &str,
+        sender: &mpsc::Sender<Vec<u8>>,
+    ) -> AppResult<()> {
+        let mut stream = conn
+            .copy_out_raw(&format!(
+                r#"copy "{name}" to stdout with (format csv, header true)"#
+            ))
+            .await?;
+        while let Some(chunk) = stream.next().await {
+            sender
+                .send(chunk?.to_vec())
+                .await
+                .map_err(|_| anyhow::anyhow!("归档文件写入已中止"))?;
+        }
+        Ok(())
+    }
+
+    // 按指定列导入带表头的CSV数据，返回导入行数
+    // 读取文件出错时中止导入
+    pub async fn copy_in(
+        conn: &mut PgConnection,
+        name: &str,
+        columns: &[String],
+        mut receiver: mpsc::Receiver<io::Result<Vec<u8>>>,
+    ) -> AppResult<u64> {
+        let columns = columns
+            .iter()
+            .map(|column| format!(r#""{}""#, column.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut copy_in = conn
+            .copy_in_raw(&format!(
+                r#"copy "{name}" ({columns}) from stdin with (format csv, header true)"#
+            ))
+            .await?;
+        while let Some(chunk) = receiver.recv().await {
+            match chunk {
+                Ok(chunk) => {
+                    copy_in.send(chunk).await?;
+                }
+                Err(e) => {
+                    copy_in.abort(e.to_string()).await?;
+                    return Err(anyhow::Error::from(e).into());
+                }
+            }
+        }
+        let rows = copy_in.finish().await?;
+        Ok(rows)
+    }
+
+    // 等待表锁的最长时间，仅对当前事务生效
+    // 卸载分区需要`account_log`的排他锁，超时后放弃，避免长时间阻塞账户写入
+    pub async fn set_lock_timeout(executor: impl PgExecutor<'_>, timeout: &str) -> AppResult<()> {
+        sqlx::query!(
+            r#"select set_config('lock_timeout', $1, true) as "lock_timeout!""#,
+            timeout
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn detach_and_drop(conn: &mut PgConnection, name: &str) -> AppResult<()> {
+        sqlx::query(&format!(
+            r#"alter table account_log detach partition "{name}""#
+        ))
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(&format!(r#"drop table "{name}""#))
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    // 按`account_log`结构创建独立的表，导入数据后再挂载为分区
+    pub async fn create_detached(executor: impl PgExecutor<'_>, name: &str) -> AppResult<()> {
+        sqlx::query(&format!(
+            r#"create table "{name}" (like account_log including defaults)"#
+        ))
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 挂载时自动创建与`account_log`一致的索引和约束
+    pub async fn attach(
+        executor: impl PgExecutor<'_>,
+        name: &str,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(&format!(
+            r#"alter table account_log attach partition "{name}" for values from ('{}') to ('{}')"#,
+            range_start.to_rfc3339(),
+            range_end.to_rfc3339()
+        ))
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
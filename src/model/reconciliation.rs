@@ -0,0 +1,111 @@
+use axum_kit::AppResult;
+use sqlx::{PgExecutor, types::Decimal};
+
+// 账户当前余额及其操作记录对应的余额
+pub struct ReconciliationModel {
+    pub account_id: i32,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    pub available_balance: Decimal,
+    pub frozen_balance: Decimal,
+    pub total_income: Decimal,
+    pub total_expense: Decimal,
+    // 最后一条操作记录的变更后余额，没有记录时为空
+    pub available_balance_after: Option<Decimal>,
+    pub frozen_balance_after: Option<Decimal>,
+    pub total_income_after: Option<Decimal>,
+    pub total_expense_after: Option<Decimal>,
+    // 第一条在线操作记录之前的余额加上全部在线操作记录的变动合计
+    // 早期分区归档后，以第一条在线记录的变更前余额为起点
+    pub sum_available_balance: Decimal,
+    pub sum_frozen_balance: Decimal,
+    pub sum_total_income: Decimal,
+    pub sum_total_expense: Decimal,
+}
+
+impl ReconciliationModel {
+    // 按账户id分批读取，单条语句内读取账户和操作记录，保证两者一致
+    // 首末记录按`id`确定：同一账户的记录在账户行锁内写入，`id`顺序即提交顺序，`created_at`为事务开始时间，顺序可能不同
+    pub async fn fetch_batch(
+        executor: impl PgExecutor<'_>,
+        after_account_id: i32,
+        user_id: Option<i32>,
+        asset_type_id: Option<i32>,
+        limit: i64,
+    ) -> AppResult<Vec<Self>> {
+        let rows = sqlx::query_as!(
+            Self,
+            r#"select
+                a.id as account_id,
+                a.user_id,
+                a.asset_type_id,
+                a.available_balance,
+                a.frozen_balance,
+                a.total_income,
+                a.total_expense,
+                l.available_balance_after as "available_balance_after?",
+                l.frozen_balance_after as "frozen_balance_after?",
+                l.total_income_after as "total_income_after?",
+                l.total_expense_after as "total_expense_after?",
+                coalesce(f.available_balance_before, 0) + s.sum_available_balance as "sum_available_balance!",
+                coalesce(f.frozen_balance_before, 0) + s.sum_frozen_balance as "sum_frozen_balance!",
+                coalesce(f.total_income_before, 0) + s.sum_total_income as "sum_total_income!",
+                coalesce(f.total_expense_before, 0) + s.sum_total_expense as "sum_total_expense!"
+            from
+                account a
+                left join lateral (
+                    select
+                        available_balance_after,
+                        frozen_balance_after,
+                        total_income_after,
+                        total_expense_after
+                    from
+                        account_log
+                    where
+                        account_id = a.id
+                    order by
+                        id desc
+                    limit 1
+                ) l on true
+                left join lateral (
+                    select
+                        available_balance_after - amount_available_balance as available_balance_before,
+                        frozen_balance_after - amount_frozen_balance as frozen_balance_before,
+                        total_income_after - amount_total_income as total_income_before,
+                        total_expense_after - amount_total_expense as total_expense_before
+                    from
+                        account_log
+                    where
+                        account_id = a.id
+                    order by
+                        id
+                    limit 1
+                ) f on true
+                cross join lateral (
+                    select
+                        coalesce(sum(amount_available_balance), 0) as sum_available_balance,
+                        coalesce(sum(amount_frozen_balance), 0) as sum_frozen_balance,
+                        coalesce(sum(amount_total_income), 0) as sum_total_income,
+                        coalesce(sum(amount_total_expense), 0) as sum_total_expense
+                    from
+                        account_log
+                    where
+                        account_id = a.id
+                ) s
+            where
+                a.id > $1
+                and ($2::int is null or a.user_id = $2)
+                and ($3::int is null or a.asset_type_id = $3)
+            order by
+                a.id
+            limit $4"#,
+            after_account_id,
+            user_id,
+            asset_type_id,
+            limit
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(rows)
+    }
+}
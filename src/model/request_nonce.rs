@@ -0,0 +1,37 @@
+use axum_kit::AppResult;
+use sqlx::PgExecutor;
+
+pub struct RequestNonceModel;
+
+impl RequestNonceModel {
+    // 记录已使用的随机数，已存在时返回`false`
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        client_id: i32,
+        nonce: &str,
+    ) -> AppResult<bool> {
+        let result = sqlx::query!(
+            r#"insert into request_nonce(client_id, nonce)
+                values ($1, $2)
+            on conflict do nothing"#,
+            client_id,
+            nonce
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    // 删除超出有效期的随机数
+    pub async fn delete_expired(executor: impl PgExecutor<'_>, max_age: i64) -> AppResult<u64> {
+        let result = sqlx::query!(
+            r#"delete from request_nonce
+            where
+                created_at < now() - make_interval(secs => $1)"#,
+            max_age as f64
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
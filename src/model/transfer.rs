@@ -0,0 +1,75 @@
+use super::serialize_utc_to_session_tz;
+use axum_kit::AppResult;
+use serde::Serialize;
+use sqlx::{
+    PgExecutor,
+    types::{
+        Decimal,
+        chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(Serialize)]
+pub struct TransferModel {
+    pub id: i64,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub asset_type_id: i32,
+    pub amount: Decimal,
+    pub order_number: String,
+    pub description: String,
+    #[serde(serialize_with = "serialize_utc_to_session_tz")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl TransferModel {
+    // 订单号已存在时返回空，并发的相同请求等待先提交的事务后返回空
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        from_user_id: i32,
+        to_user_id: i32,
+        asset_type_id: i32,
+        amount: Decimal,
+        order_number: &str,
+        description: &str,
+    ) -> AppResult<Option<Self>> {
+        let transfer = sqlx::query_as!(
+            Self,
+            r#"insert into transfer(from_user_id, to_user_id, asset_type_id, amount, order_number, description)
+                values ($1, $2, $3, $4, $5, $6)
+            on conflict (order_number) do nothing
+            returning
+                id,
+                from_user_id,
+                to_user_id,
+                asset_type_id,
+                amount,
+                order_number,
+                description,
+                created_at"#,
+            from_user_id,
+            to_user_id,
+            asset_type_id,
+            amount,
+            order_number,
+            description
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(transfer)
+    }
+
+    // 转账订单是否存在
+    pub async fn is_exists(executor: impl PgExecutor<'_>, order_number: &str) -> bool {
+        if let Ok(Some(exists)) = sqlx::query_scalar!(
+            r#"select exists(select 1 from transfer where order_number = $1)"#,
+            order_number
+        )
+        .fetch_one(executor)
+        .await
+        {
+            return exists;
+        }
+        false
+    }
+}
@@ -0,0 +1,380 @@
+use super::outbox::{BalanceChange, EventType};
+use axum_kit::AppResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    PgExecutor,
+    types::{
+        JsonValue,
+        chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(Serialize)]
+pub struct WebhookSubscriptionModel {
+    pub id: i32,
+    pub client_id: i32,
+    pub url: String,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub asset_type_ids: Vec<i32>,
+    pub action_type_ids: Vec<i32>,
+    pub is_active: bool,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub created_at: DateTime<Utc>,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookSubscriptionModel {
+    pub async fn create(
+        executor: impl PgExecutor<'_>,
+        client_id: i32,
+        url: &str,
+        secret: &str,
+        asset_type_ids: &[i32],
+        action_type_ids: &[i32],
+    ) -> AppResult<Self> {
+        let subscription = sqlx::query_as!(
+            Self,
+            r#"insert into webhook_subscription(client_id, url, secret, asset_type_ids, action_type_ids)
+                values ($1, $2, $3, $4, $5)
+            returning
+                id,
+                client_id,
+                url,
+                secret,
+                asset_type_ids,
+                action_type_ids,
+                is_active,
+                created_at,
+                updated_at"#,
+            client_id,
+            url,
+            secret,
+            asset_type_ids,
+            action_type_ids
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(subscription)
+    }
+
+    // 尚未加密的签名密钥
+    pub async fn plaintext_secrets(
+        executor: impl PgExecutor<'_>,
+        encrypted_prefix: &str,
+    ) -> AppResult<Vec<(i32, String)>> {
+        let rows = sqlx::query!(
+            r#"select
+                id,
+                secret
+            from
+                webhook_subscription
+            where
+                not starts_with(secret, $1)"#,
+            encrypted_prefix
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(rows.into_iter().map(|row| (row.id, row.secret)).collect())
+    }
+
+    // 将明文签名密钥替换为密文，密钥已被修改时不更新
+    pub async fn encrypt_secret(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        plaintext: &str,
+        encrypted: &str,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            r#"update webhook_subscription
+                set secret = $3
+            where
+                id = $1
+                and secret = $2"#,
+            id,
+            plaintext,
+            encrypted
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 仅更新传入的字段
+    pub async fn update(
+        executor: impl PgExecutor<'_>,
+        id: i32,
+        url: Option<&str>,
+        asset_type_ids: Option<&[i32]>,
+        action_type_ids: Option<&[i32]>,
+        is_active: Option<bool>,
+    ) -> AppResult<Option<Self>> {
+        let subscription = sqlx::query_as!(
+            Self,
+            r#"update webhook_subscription
+                set url = coalesce($2, url),
+                asset_type_ids = coalesce($3, asset_type_ids),
+                action_type_ids = coalesce($4, action_type_ids),
+                is_active = coalesce($5, is_active)
+            where
+                id = $1
+            returning
+                id,
+                client_id,
+                url,
+                secret,
+                asset_type_ids,
+                action_type_ids,
+                is_active,
+                created_at,
+                updated_at"#,
+            id,
+            url,
+            asset_type_ids,
+            action_type_ids,
+            is_active
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(subscription)
+    }
+}
+
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Clone, Copy, Debug)]
+#[sqlx(type_name = "webhook_delivery_status_enum", rename_all = "UPPERCASE")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Succeeded,
+    Dead,
+}
+
+#[derive(Serialize)]
+pub struct WebhookDeliveryModel {
+    pub id: i64,
+    pub subscription_id: i32,
+    pub event_type: String,
+    pub payload: JsonValue,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: String,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+// 待投递的回调，附带订阅的回调地址和加密的签名密钥
+pub struct WebhookDeliveryTask {
+    pub id: i64,
+    pub event_type: String,
+    pub payload: JsonValue,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub url: String,
+    pub secret: String,
+}
+
+impl WebhookDeliveryModel {
+    // 按订阅条件为每个账户的余额变更生成投递记录，与余额变更在同一事务内写入
+    // 只投递客户端允许使用的资产类型
+    pub async fn fan_out(
+        executor: impl PgExecutor<'_>,
+        event_type: EventType,
+        changes: &[BalanceChange],
+    ) -> AppResult<()> {
+        let changes = serde_json::to_value(changes).map_err(anyhow::Error::from)?;
+        sqlx::query!(
+            r#"insert into webhook_delivery(subscription_id, event_type, payload)
+            select
+                s.id,
+                $1,
+                c.change
+            from
+                jsonb_array_elements($2) with ordinality as c(change, n)
+                join webhook_subscription s on s.is_active
+                join client on client.id = s.client_id
+                    and client.is_active
+            where
+                (c.change->>'asset_type_id')::int = any(client.asset_type_ids)
+                and (cardinality(s.asset_type_ids) = 0
+                    or (c.change->>'asset_type_id')::int = any(s.asset_type_ids))
+                and (cardinality(s.action_type_ids) = 0
+                    or (c.change->>'action_type_id')::int = any(s.action_type_ids))
+            order by
+                c.n,
+                s.id"#,
+            event_type.as_str(),
+            changes
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 领取一批到期的投递记录，将下次尝试时间推迟`lease`秒作为租约，语句结束即提交
+    // 多实例部署时跳过其他实例正在领取的记录，租约到期仍未标记结果的记录会被再次领取
+    pub async fn claim_due(
+        executor: impl PgExecutor<'_>,
+        limit: i64,
+        lease: i64,
+    ) -> AppResult<Vec<WebhookDeliveryTask>> {
+        let tasks = sqlx::query_as!(
+            WebhookDeliveryTask,
+            r#"with claimed as (
+                update webhook_delivery
+                    set next_attempt_at = now() + make_interval(secs => $2::bigint)
+                where
+                    id in (
+                        select
+                            id
+                        from
+                            webhook_delivery
+                        where
+                            status = 'PENDING'
+                            and next_attempt_at <= now()
+                        order by
+                            next_attempt_at,
+                            id
+                        limit $1
+                        for update skip locked
+                    )
+                returning
+                    id,
+                    subscription_id,
+                    event_type,
+                    payload,
+                    attempts,
+                    created_at
+            )
+            select
+                c.id,
+                c.event_type,
+                c.payload,
+                c.attempts,
+                c.created_at,
+                s.url,
+                s.secret
+            from
+                claimed c
+                join webhook_subscription s on s.id = c.subscription_id"#,
+            limit,
+            lease
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(tasks)
+    }
+
+    pub async fn mark_succeeded(executor: impl PgExecutor<'_>, id: i64) -> AppResult<()> {
+        sqlx::query!(
+            r#"update webhook_delivery
+                set status = 'SUCCEEDED',
+                attempts = attempts + 1,
+                last_error = '',
+                delivered_at = now()
+            where
+                id = $1"#,
+            id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 记录失败原因，`retry_after`为空时不再重试
+    pub async fn mark_failed(
+        executor: impl PgExecutor<'_>,
+        id: i64,
+        error: &str,
+        retry_after: Option<i64>,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            r#"update webhook_delivery
+                set status = case when $3::bigint is null
+                    then 'DEAD'::webhook_delivery_status_enum
+                    else 'PENDING'::webhook_delivery_status_enum
+                end,
+                attempts = attempts + 1,
+                last_error = $2,
+                next_attempt_at = now() + make_interval(secs => coalesce($3, 0))
+            where
+                id = $1"#,
+            id,
+            error,
+            retry_after
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 重新投递，尝试次数清零
+    pub async fn redeliver(executor: impl PgExecutor<'_>, id: i64) -> AppResult<Option<Self>> {
+        let delivery = sqlx::query_as!(
+            Self,
+            r#"update webhook_delivery
+                set status = 'PENDING',
+                attempts = 0,
+                next_attempt_at = now(),
+                delivered_at = null
+            where
+                id = $1
+            returning
+                id,
+                subscription_id,
+                event_type,
+                payload,
+                status as "status!: WebhookDeliveryStatus",
+                attempts,
+                next_attempt_at,
+                last_error,
+                created_at,
+                delivered_at"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+        Ok(delivery)
+    }
+
+    // 按订阅和状态查询投递记录，按id倒序
+    pub async fn fetch(
+        executor: impl PgExecutor<'_>,
+        subscription_id: i32,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+    ) -> AppResult<Vec<Self>> {
+        let deliveries = sqlx::query_as!(
+            Self,
+            r#"select
+                id,
+                subscription_id,
+                event_type,
+                payload,
+                status as "status!: WebhookDeliveryStatus",
+                attempts,
+                next_attempt_at,
+                last_error,
+                created_at,
+                delivered_at
+            from
+                webhook_delivery
+            where
+                subscription_id = $1
+                and ($2::webhook_delivery_status_enum is null or status = $2)
+            order by
+                id desc
+            limit $3"#,
+            subscription_id,
+            status as Option<WebhookDeliveryStatus>,
+            limit
+        )
+        .fetch_all(executor)
+        .await?;
+        Ok(deliveries)
+    }
+}
@@ -1,6 +1,11 @@
 use crate::{
-    constant::{MAX_PAGE_SIZE, MIN_PAGE, MIN_PAGE_SIZE},
+    constant::{
+        HOLD_DEFAULT_EXPIRES_IN, HOLD_MAX_EXPIRES_IN, HOLD_MIN_EXPIRES_IN, MAX_PAGE_SIZE, MIN_PAGE,
+        MIN_PAGE_SIZE,
+    },
+    model::{action_type::Change, webhook::WebhookDeliveryStatus},
     service::{action_type::ActionTypeService, asset_type::AssetTypeService},
+    utils,
 };
 use serde::{Deserialize, Deserializer};
 use sqlx::types::Decimal;
@@ -21,6 +26,28 @@ pub struct AccountsRequest {
     pub user_id: i32,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct AccountActionsQuery {
+    // 仅预览，不实际执行
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct AccountBatchRequest {
+    #[validate(range(min = 1, message = "批次ID必须为正整数"))]
+    pub batch_id: i64,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct AccountSubscribeRequest {
+    #[validate(range(min = 1, message = "用户ID必须为正整数"))]
+    pub user_id: i32,
+    // 为空时订阅客户端允许使用的全部资产类型
+    #[validate(custom(function = "validate_asset_type_id"))]
+    pub asset_type_id: Option<i32>,
+}
+
 #[derive(Deserialize, Validate, Debug)]
 pub struct AccountActionRequest {
     #[validate(range(min = 1, message = "用户ID必须为正整数"))]
@@ -38,6 +65,94 @@ pub struct AccountActionRequest {
     pub description: String,
 }
 
+#[derive(Deserialize, Validate, Debug)]
+#[validate(schema(function = "validate_transfer"))]
+pub struct AccountTransferRequest {
+    #[validate(range(min = 1, message = "转出用户ID必须为正整数"))]
+    pub from_user_id: i32,
+    #[validate(custom(function = "validate_asset_type_id"))]
+    pub from_asset_type_id: i32,
+    #[validate(range(min = 1, message = "转入用户ID必须为正整数"))]
+    pub to_user_id: i32,
+    #[validate(custom(function = "validate_asset_type_id"))]
+    pub to_asset_type_id: i32,
+    #[validate(custom(function = "validate_amount"))]
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub amount: Decimal,
+    #[validate(length(min = 32, message = "订单号长度至少32位"))]
+    pub order_number: String,
+    #[validate(length(min = 1, message = "描述不能为空"))]
+    pub description: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct AccountReversalRequest {
+    #[validate(range(min = 1, message = "用户ID必须为正整数"))]
+    pub user_id: i32,
+    #[validate(custom(function = "validate_asset_type_id"))]
+    pub asset_type_id: i32,
+    #[validate(length(min = 1, message = "原订单号不能为空"))]
+    pub order_number: String,
+    #[validate(custom(function = "validate_any_action_type_id"))]
+    pub action_type_id: Option<i32>,
+    #[validate(custom(function = "validate_amount"))]
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub amount: Option<Decimal>,
+    #[validate(length(min = 32, message = "冲正订单号长度至少32位"))]
+    pub reversal_order_number: String,
+    #[validate(length(min = 1, message = "描述不能为空"))]
+    pub description: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct HoldAuthorizeRequest {
+    #[validate(range(min = 1, message = "用户ID必须为正整数"))]
+    pub user_id: i32,
+    #[validate(custom(function = "validate_asset_type_id"))]
+    pub asset_type_id: i32,
+    #[validate(custom(function = "validate_amount"))]
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub amount: Decimal,
+    #[validate(length(min = 32, message = "订单号长度至少32位"))]
+    pub order_number: String,
+    #[validate(length(min = 1, message = "描述不能为空"))]
+    pub description: String,
+    #[validate(range(
+        min = "HOLD_MIN_EXPIRES_IN",
+        max = "HOLD_MAX_EXPIRES_IN",
+        message = "有效期超出允许范围"
+    ))]
+    #[serde(default = "default_hold_expires_in")]
+    pub expires_in: i64,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct HoldCaptureRequest {
+    #[validate(range(min = 1, message = "预授权ID必须为正整数"))]
+    pub hold_id: i64,
+    #[validate(custom(function = "validate_amount"))]
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub amount: Decimal,
+    #[validate(length(min = 32, message = "订单号长度至少32位"))]
+    pub order_number: String,
+    #[validate(length(min = 1, message = "描述不能为空"))]
+    pub description: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct HoldVoidRequest {
+    #[validate(range(min = 1, message = "预授权ID必须为正整数"))]
+    pub hold_id: i64,
+    #[validate(length(min = 1, message = "描述不能为空"))]
+    pub description: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct HoldRequest {
+    #[validate(range(min = 1, message = "预授权ID必须为正整数"))]
+    pub hold_id: i64,
+}
+
 #[derive(Deserialize, Validate, Debug)]
 #[validate(schema(function = "validate_time_range"))]
 pub struct AccountLogRequest {
@@ -45,20 +160,288 @@ pub struct AccountLogRequest {
     pub user_id: i32,
     #[validate(custom(function = "validate_asset_type_id"))]
     pub asset_type_id: i32,
-    #[validate(custom(function = "validate_action_type_id"))]
+    #[validate(custom(function = "validate_any_action_type_id"))]
     pub action_type_id: Option<i32>,
     #[validate(custom(function = "validate_date_format"))]
     pub start_time: Option<String>,
     #[validate(custom(function = "validate_date_format"))]
     pub end_time: Option<String>,
     #[validate(range(min = "MIN_PAGE"))]
+    #[serde(default = "default_page")]
     pub page: i32,
     #[validate(range(min = "MIN_PAGE_SIZE", max = "MAX_PAGE_SIZE"))]
     pub page_size: i32,
+    // 上一页返回的游标，传入时忽略`page`
+    #[validate(custom(function = "validate_cursor"))]
+    pub cursor: Option<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[validate(schema(function = "validate_activity_time_range"))]
+pub struct AccountActivityRequest {
+    #[validate(range(min = 1, message = "用户ID必须为正整数"))]
+    pub user_id: i32,
+    #[validate(custom(function = "validate_any_action_type_id"))]
+    pub action_type_id: Option<i32>,
+    #[validate(custom(function = "validate_date_format"))]
+    pub start_time: Option<String>,
+    #[validate(custom(function = "validate_date_format"))]
+    pub end_time: Option<String>,
+    #[validate(range(min = "MIN_PAGE"))]
+    #[serde(default = "default_page")]
+    pub page: i32,
+    #[validate(range(min = "MIN_PAGE_SIZE", max = "MAX_PAGE_SIZE"))]
+    pub page_size: i32,
+    // 上一页返回的游标，传入时忽略`page`
+    #[validate(custom(function = "validate_cursor"))]
+    pub cursor: Option<String>,
+}
+
+// 导出格式
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[validate(schema(function = "validate_export_time_range"))]
+pub struct AccountLogExportRequest {
+    #[validate(range(min = 1, message = "用户ID必须为正整数"))]
+    pub user_id: i32,
+    #[validate(custom(function = "validate_asset_type_id"))]
+    pub asset_type_id: i32,
+    #[validate(custom(function = "validate_any_action_type_id"))]
+    pub action_type_id: Option<i32>,
+    #[validate(custom(function = "validate_date_format"))]
+    pub start_time: Option<String>,
+    #[validate(custom(function = "validate_date_format"))]
+    pub end_time: Option<String>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct AssetTypeCreateRequest {
+    #[validate(length(min = 1, max = 32, message = "资产类型名称长度应为1-32位"))]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct AssetTypeUpdateRequest {
+    #[validate(range(min = 1, message = "资产类型ID必须为正整数"))]
+    pub id: i32,
+    #[validate(length(min = 1, max = 32, message = "资产类型名称长度应为1-32位"))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct AssetTypeActiveRequest {
+    #[validate(range(min = 1, message = "资产类型ID必须为正整数"))]
+    pub id: i32,
+    // 停用时存在余额不为零的账户仍强制停用
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ActionTypeCreateRequest {
+    #[validate(length(min = 1, max = 32, message = "操作类型名称长度应为1-32位"))]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub available_balance_change: Change,
+    pub frozen_balance_change: Change,
+    pub total_income_change: Change,
+    pub total_expense_change: Change,
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ActionTypeUpdateRequest {
+    #[validate(range(min = 1, message = "操作类型ID必须为正整数"))]
+    pub id: i32,
+    #[validate(length(min = 1, max = 32, message = "操作类型名称长度应为1-32位"))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub available_balance_change: Option<Change>,
+    pub frozen_balance_change: Option<Change>,
+    pub total_income_change: Option<Change>,
+    pub total_expense_change: Option<Change>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ActionTypeActiveRequest {
+    #[validate(range(min = 1, message = "操作类型ID必须为正整数"))]
+    pub id: i32,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ClientCreateRequest {
+    #[validate(length(min = 1, max = 64, message = "客户端名称长度应为1-64位"))]
+    pub name: String,
+    #[serde(default)]
+    pub asset_type_ids: Vec<i32>,
+    #[serde(default)]
+    pub action_type_ids: Vec<i32>,
+    // 是否启用请求签名
+    #[serde(default)]
+    pub signing: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ClientUpdateRequest {
+    #[validate(range(min = 1, message = "客户端ID必须为正整数"))]
+    pub id: i32,
+    pub asset_type_ids: Option<Vec<i32>>,
+    pub action_type_ids: Option<Vec<i32>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ClientSigningRequest {
+    #[validate(range(min = 1, message = "客户端ID必须为正整数"))]
+    pub id: i32,
+    // 启用时生成新的签名密钥，停用时清除签名密钥
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct WebhookCreateRequest {
+    #[validate(range(min = 1, message = "客户端ID必须为正整数"))]
+    pub client_id: i32,
+    #[validate(url(message = "回调地址格式无效"))]
+    pub url: String,
+    // 为空时订阅客户端允许使用的全部资产类型
+    #[serde(default)]
+    pub asset_type_ids: Vec<i32>,
+    // 为空时订阅全部操作类型
+    #[serde(default)]
+    pub action_type_ids: Vec<i32>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct WebhookUpdateRequest {
+    #[validate(range(min = 1, message = "订阅ID必须为正整数"))]
+    pub id: i32,
+    #[validate(url(message = "回调地址格式无效"))]
+    pub url: Option<String>,
+    pub asset_type_ids: Option<Vec<i32>>,
+    pub action_type_ids: Option<Vec<i32>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct WebhookDeliveriesRequest {
+    #[validate(range(min = 1, message = "订阅ID必须为正整数"))]
+    pub subscription_id: i32,
+    // 为空时查询全部状态
+    pub status: Option<WebhookDeliveryStatus>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct WebhookRedeliverRequest {
+    #[validate(range(min = 1, message = "投递ID必须为正整数"))]
+    pub id: i64,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ReconciliationRequest {
+    // 为空时核对全部用户
+    #[validate(range(min = 1, message = "用户ID必须为正整数"))]
+    pub user_id: Option<i32>,
+    // 为空时核对全部资产类型
+    #[validate(range(min = 1, message = "资产类型ID必须为正整数"))]
+    pub asset_type_id: Option<i32>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct OrderLogRequest {
+    #[validate(length(min = 1, message = "订单号不能为空"))]
+    pub order_number: String,
+    // 格式为`YYYY-MM`（UTC），指定时只查询该月份的分区
+    #[validate(custom(function = "validate_month_format"))]
+    pub month: Option<String>,
+}
+
+impl OrderLogRequest {
+    // 月份的UTC起止时间
+    pub fn month_range(
+        &self,
+    ) -> anyhow::Result<Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>>
+    {
+        let Some(month) = &self.month else {
+            return Ok(None);
+        };
+        let start = chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")?;
+        let end = start
+            .checked_add_months(chrono::Months::new(1))
+            .ok_or_else(|| anyhow::anyhow!("月份{}无效", month))?;
+        Ok(Some((
+            start.and_time(chrono::NaiveTime::MIN).and_utc(),
+            end.and_time(chrono::NaiveTime::MIN).and_utc(),
+        )))
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ArchiveMonthRequest {
+    // 格式为`YYYY-MM`
+    #[validate(custom(function = "validate_month_format"))]
+    pub month: String,
+}
+
+impl ArchiveMonthRequest {
+    // 月份第一天
+    pub fn month(&self) -> anyhow::Result<chrono::NaiveDate> {
+        Ok(chrono::NaiveDate::parse_from_str(
+            &format!("{}-01", self.month),
+            "%Y-%m-%d",
+        )?)
+    }
 }
 
 fn validate_time_range(request: &AccountLogRequest) -> Result<(), ValidationError> {
-    if let (Some(start_time), Some(end_time)) = (&request.start_time, &request.end_time) {
+    check_time_range(&request.start_time, &request.end_time)
+}
+
+fn validate_activity_time_range(request: &AccountActivityRequest) -> Result<(), ValidationError> {
+    check_time_range(&request.start_time, &request.end_time)
+}
+
+fn validate_export_time_range(request: &AccountLogExportRequest) -> Result<(), ValidationError> {
+    check_time_range(&request.start_time, &request.end_time)
+}
+
+fn check_time_range(
+    start_time: &Option<String>,
+    end_time: &Option<String>,
+) -> Result<(), ValidationError> {
+    if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
         let start = chrono::NaiveDate::parse_from_str(start_time, "%Y-%m-%d");
         let end = chrono::NaiveDate::parse_from_str(end_time, "%Y-%m-%d");
         if let (Ok(start), Ok(end)) = (start, end)
@@ -71,22 +454,54 @@ fn validate_time_range(request: &AccountLogRequest) -> Result<(), ValidationErro
     Ok(())
 }
 
-fn validate_asset_type_id(id: i32) -> Result<(), ValidationError> {
-    if !AssetTypeService::is_active(id) {
-        return Err(
-            ValidationError::new("asset_type_id").with_message(Cow::Borrowed("无效的资产类型"))
-        );
+fn validate_transfer(request: &AccountTransferRequest) -> Result<(), ValidationError> {
+    if request.from_asset_type_id != request.to_asset_type_id {
+        return Err(ValidationError::new("asset_type_id")
+            .with_message(Cow::Borrowed("转出与转入资产类型不一致")));
+    }
+    if request.from_user_id == request.to_user_id {
+        return Err(ValidationError::new("user_id").with_message(Cow::Borrowed("不能向自己转账")));
     }
     Ok(())
 }
 
+// 缓存未加载的请求已由`cache::ready`返回503，此处仍按校验失败处理而非终止
+fn validate_asset_type_id(id: i32) -> Result<(), ValidationError> {
+    match AssetTypeService::is_active(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            Err(ValidationError::new("asset_type_id").with_message(Cow::Borrowed("无效的资产类型")))
+        }
+        Err(_) => {
+            Err(ValidationError::new("asset_type_id")
+                .with_message(Cow::Borrowed("资产类型缓存未加载")))
+        }
+    }
+}
+
 fn validate_action_type_id(id: i32) -> Result<(), ValidationError> {
-    if !ActionTypeService::is_active(id) {
-        return Err(
-            ValidationError::new("action_type_id").with_message(Cow::Borrowed("无效的操作类型"))
-        );
+    match ActionTypeService::is_active(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            Err(ValidationError::new("action_type_id")
+                .with_message(Cow::Borrowed("无效的操作类型")))
+        }
+        Err(_) => Err(ValidationError::new("action_type_id")
+            .with_message(Cow::Borrowed("操作类型缓存未加载"))),
+    }
+}
+
+// 查询历史记录时允许已停用的操作类型
+fn validate_any_action_type_id(id: i32) -> Result<(), ValidationError> {
+    match ActionTypeService::is_exists(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            Err(ValidationError::new("action_type_id")
+                .with_message(Cow::Borrowed("无效的操作类型")))
+        }
+        Err(_) => Err(ValidationError::new("action_type_id")
+            .with_message(Cow::Borrowed("操作类型缓存未加载"))),
     }
-    Ok(())
 }
 
 fn validate_amount(amount: &Decimal) -> Result<(), ValidationError> {
@@ -112,6 +527,30 @@ fn validate_date_format(date: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_cursor(cursor: &str) -> Result<(), ValidationError> {
+    if utils::decode_cursor(cursor).is_err() {
+        return Err(ValidationError::new("cursor").with_message(Cow::Borrowed("无效的分页游标")));
+    }
+    Ok(())
+}
+
+fn validate_month_format(month: &str) -> Result<(), ValidationError> {
+    if chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").is_err() {
+        return Err(
+            ValidationError::new("month").with_message(Cow::Borrowed("月份格式无效，应为YYYY-MM"))
+        );
+    }
+    Ok(())
+}
+
+fn default_page() -> i32 {
+    MIN_PAGE
+}
+
+fn default_hold_expires_in() -> i64 {
+    HOLD_DEFAULT_EXPIRES_IN
+}
+
 fn deserialize_decimal<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
 where
     D: Deserializer<'de>,
@@ -119,3 +558,12 @@ where
     let s: String = String::deserialize(deserializer)?;
     Decimal::from_str(&s).map_err(|_| serde::de::Error::custom("请输入有效金额"))
 }
+
+fn deserialize_optional_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| Decimal::from_str(&s).map_err(|_| serde::de::Error::custom("请输入有效金额")))
+        .transpose()
+}
@@ -0,0 +1,237 @@
+use crate::{
+    model::{
+        account_log::{AccountBalance, AccountLogModel, AccountLogSummary, BatchActionResult},
+        client::ClientModel,
+        partition::PartitionModel,
+        webhook::WebhookSubscriptionModel,
+    },
+    request::AccountActionRequest,
+};
+use serde::Serialize;
+use sqlx::types::Decimal;
+
+#[derive(Serialize)]
+pub struct ClientCreatedResponse {
+    #[serde(flatten)]
+    pub client: ClientModel,
+    // API密钥仅在创建时返回一次
+    pub api_key: String,
+    // 签名密钥仅在生成时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ClientSigningResponse {
+    #[serde(flatten)]
+    pub client: ClientModel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct WebhookCreatedResponse {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscriptionModel,
+    // 回调签名密钥仅在创建时返回一次
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+pub struct AccountActionsResponse {
+    // 迁移前处理的请求重试时为空
+    pub batch_id: Option<i64>,
+    pub results: Vec<BatchActionResult>,
+}
+
+// 单个账户操作的预览结果
+#[derive(Serialize)]
+pub struct ActionPreview {
+    // 在请求中的序号，从0开始
+    pub index: usize,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    pub action_type_id: i32,
+    pub amount_available_balance: Decimal,
+    pub amount_frozen_balance: Decimal,
+    pub amount_total_income: Decimal,
+    pub amount_total_expense: Decimal,
+    pub available_balance_after: Decimal,
+    pub frozen_balance_after: Decimal,
+    pub total_income_after: Decimal,
+    pub total_expense_after: Decimal,
+}
+
+impl ActionPreview {
+    pub fn new(
+        index: usize,
+        account_action_request: &AccountActionRequest,
+        account_log: &AccountLogModel,
+    ) -> Self {
+        Self {
+            index,
+            user_id: account_action_request.user_id,
+            asset_type_id: account_action_request.asset_type_id,
+            action_type_id: account_log.action_type_id,
+            amount_available_balance: account_log.amount_available_balance,
+            amount_frozen_balance: account_log.amount_frozen_balance,
+            amount_total_income: account_log.amount_total_income,
+            amount_total_expense: account_log.amount_total_expense,
+            available_balance_after: account_log.available_balance_after,
+            frozen_balance_after: account_log.frozen_balance_after,
+            total_income_after: account_log.total_income_after,
+            total_expense_after: account_log.total_expense_after,
+        }
+    }
+}
+
+// 预览失败的操作，`status`为实际执行时将返回的状态码
+#[derive(Serialize)]
+pub struct PreviewFailure {
+    pub index: usize,
+    pub status: u16,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct AccountActionsPreview {
+    pub success: bool,
+    // 失败时只包含失败操作之前的结果
+    pub results: Vec<ActionPreview>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<PreviewFailure>,
+}
+
+// 用户操作记录，附带所属资产类型
+#[derive(Serialize)]
+pub struct AccountActivity {
+    pub asset_type_id: i32,
+    #[serde(flatten)]
+    pub account_log: AccountLogModel,
+}
+
+#[derive(Serialize)]
+pub struct AccountActivityResponse {
+    pub list: Vec<AccountActivity>,
+    // 下一页游标，没有更多记录时为空
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AccountLogsResponse {
+    pub list: Vec<AccountLogModel>,
+    // 符合查询条件的记录总数及各金额变动合计
+    #[serde(flatten)]
+    pub summary: AccountLogSummary,
+    // 查询时间范围开始时的账户余额
+    pub opening_balance: AccountBalance,
+    // 查询时间范围结束时的账户余额
+    pub closing_balance: AccountBalance,
+    // 下一页游标，没有更多记录时为空
+    pub next_cursor: Option<String>,
+}
+
+// 对账依据
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DriftSource {
+    // 最后一条操作记录的变更后余额
+    LastLog,
+    // 全部在线操作记录的变动合计（以第一条在线记录的变更前余额为起点）
+    LogSum,
+}
+
+#[derive(Serialize)]
+pub struct ReconciliationDrift {
+    pub account_id: i32,
+    pub user_id: i32,
+    pub asset_type_id: i32,
+    pub source: DriftSource,
+    pub field: &'static str,
+    // 按操作记录计算的余额
+    pub expected: Decimal,
+    // 账户当前余额
+    pub actual: Decimal,
+    pub delta: Decimal,
+}
+
+#[derive(Serialize)]
+pub struct ReconciliationReport {
+    pub checked_accounts: usize,
+    pub drifts: Vec<ReconciliationDrift>,
+}
+
+// 哈希链断裂原因
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChainBreak {
+    // 记录内容与哈希不一致，记录被修改
+    HashMismatch,
+    // 上一条哈希与前一条记录不一致，记录被删除或插入
+    PrevHashMismatch,
+    // 哈希链开始后出现没有哈希的记录
+    MissingHash,
+    // 第一条哈希记录与账户锚定的起始记录不一致，最早的记录被删除
+    MissingHead,
+    // 最后一条记录的哈希与账户锚点不一致，最新的记录被删除
+    TailMismatch,
+}
+
+#[derive(Serialize)]
+pub struct BrokenLink {
+    pub reason: ChainBreak,
+    // 锚定的记录已全部删除时为空
+    pub log_id: Option<i64>,
+    #[serde(flatten)]
+    pub log: Option<AccountLogModel>,
+}
+
+impl BrokenLink {
+    pub fn new(reason: ChainBreak, log: AccountLogModel) -> Self {
+        Self {
+            reason,
+            log_id: Some(log.id),
+            log: Some(log),
+        }
+    }
+
+    pub fn without_log(reason: ChainBreak) -> Self {
+        Self {
+            reason,
+            log_id: None,
+            log: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ChainVerification {
+    pub account_id: i32,
+    pub checked_logs: usize,
+    // 校验通过的最后一条记录哈希，可留存用于后续比对
+    pub last_hash: Option<String>,
+    // 第一处断裂，哈希链完整时为空
+    pub broken: Option<BrokenLink>,
+}
+
+#[derive(Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    // 检查失败的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+#[derive(Serialize)]
+pub struct PartitionStatus {
+    pub months_ahead: i32,
+    pub partitions: Vec<PartitionModel>,
+    pub missing: Vec<String>,
+}
@@ -0,0 +1,81 @@
+use crate::{
+    handler,
+    middleware::{admin, cache},
+};
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+
+// 管理接口，需通过管理令牌鉴权
+pub fn init() -> Router {
+    Router::new()
+        // 添加资产类型
+        .route("/admin/assets/new", post(handler::asset_type::create))
+        // 修改资产类型
+        .route("/admin/assets/update", post(handler::asset_type::update))
+        // 启用资产类型
+        .route(
+            "/admin/assets/activate",
+            post(handler::asset_type::activate),
+        )
+        // 停用资产类型
+        .route(
+            "/admin/assets/deactivate",
+            post(handler::asset_type::deactivate),
+        )
+        // 添加账户操作类型
+        .route("/admin/actions/new", post(handler::action_type::create))
+        // 修改账户操作类型
+        .route("/admin/actions/update", post(handler::action_type::update))
+        // 启用账户操作类型
+        .route(
+            "/admin/actions/activate",
+            post(handler::action_type::activate),
+        )
+        // 停用账户操作类型
+        .route(
+            "/admin/actions/deactivate",
+            post(handler::action_type::deactivate),
+        )
+        // 添加客户端
+        .route("/admin/clients/new", post(handler::client::create))
+        // 修改客户端
+        .route("/admin/clients/update", post(handler::client::update))
+        // 启用或停用客户端请求签名
+        .route("/admin/clients/signing", post(handler::client::signing))
+        // 添加回调订阅
+        .route("/admin/webhooks/new", post(handler::webhook::create))
+        // 修改回调订阅
+        .route("/admin/webhooks/update", post(handler::webhook::update))
+        // 回调投递记录
+        .route(
+            "/admin/webhooks/deliveries",
+            post(handler::webhook::deliveries),
+        )
+        // 重新投递回调
+        .route(
+            "/admin/webhooks/redeliver",
+            post(handler::webhook::redeliver),
+        )
+        // 按订单号查询操作记录
+        .route("/admin/accounts/orders", post(handler::account::order_logs))
+        // 校验账户哈希链
+        .route(
+            "/admin/accounts/verify",
+            post(handler::account::verify_chain).route_layer(middleware::from_fn(cache::ready)),
+        )
+        // 分区状态
+        .route("/admin/partitions", get(handler::partition::status))
+        // 归档清单
+        .route("/admin/archives", get(handler::archive::list))
+        // 立即归档超出保留期的分区
+        .route("/admin/archives/run", post(handler::archive::run))
+        // 重新挂载已归档的月份
+        .route("/admin/archives/restore", post(handler::archive::restore))
+        // 卸载重新挂载的已归档月份
+        .route("/admin/archives/unload", post(handler::archive::unload))
+        // 对账
+        .route("/admin/reconciliation", post(handler::reconciliation::run))
+        .route_layer(middleware::from_fn(admin::auth))
+}
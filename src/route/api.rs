@@ -1,6 +1,9 @@
-use crate::handler;
+use crate::{
+    handler,
+    middleware::{auth, cache, metrics, signature},
+};
 use axum::{
-    Router,
+    Router, middleware,
     routing::{get, post},
 };
 use axum_kit::middleware::{cors, request_id, trace, trace_body};
@@ -20,14 +23,49 @@ pub fn init() -> Router {
         .route("/accounts/infos", post(handler::account::infos))
         // 资产账户操作
         .route("/accounts/actions", post(handler::account::actions))
+        // 按批次id查询批量操作结果
+        .route("/accounts/batch", post(handler::account::batch))
+        // 订阅余额变更
+        .route("/accounts/subscribe", post(handler::account::subscribe))
+        // 用户间转账
+        .route("/accounts/transfer", post(handler::account::transfer))
+        // 按订单号冲正资产账户操作
+        .route("/accounts/reverse", post(handler::account::reverse))
         // 资产账户操作记录
         .route("/accounts/logs", post(handler::account::logs))
+        // 用户全部资产账户操作记录
+        .route("/accounts/activity", post(handler::account::activity))
+        // 导出资产账户操作记录
+        .route("/accounts/logs/export", post(handler::account::export))
+        // 预授权冻结
+        .route("/holds/authorize", post(handler::hold::authorize))
+        // 预授权扣款
+        .route("/holds/capture", post(handler::hold::capture))
+        // 预授权撤销
+        .route("/holds/void", post(handler::hold::void))
+        // 预授权信息
+        .route("/holds/info", post(handler::hold::info))
+        // 以上接口需通过API密钥鉴权，启用请求签名的客户端还需校验签名，缓存加载前返回503
+        .route_layer(middleware::from_fn(cache::ready))
+        .route_layer(middleware::from_fn(signature::verify))
+        .route_layer(middleware::from_fn(auth::api_key))
+        // Prometheus 指标，需通过指标令牌鉴权
+        .route(
+            "/metrics",
+            get(handler::metrics::render).route_layer(middleware::from_fn(metrics::auth)),
+        )
+        // 存活检查，无需鉴权
+        .route("/healthz", get(handler::health::healthz))
+        // 就绪检查，无需鉴权
+        .route("/readyz", get(handler::health::readyz))
+        .merge(super::admin::init())
         .layer(
             ServiceBuilder::new()
                 .layer(request_id::set_request_id())
                 .layer(request_id::propagate_request_id())
                 .layer(trace::trace())
                 .layer(cors::cors())
-                .layer(trace_body::trace_body()),
+                .layer(trace_body::trace_body())
+                .layer(middleware::from_fn(metrics::track)),
         )
 }
@@ -1,16 +1,46 @@
-use super::{action_type::ActionTypeService, asset_type::AssetTypeService};
+use super::{
+    action_type::ActionTypeService,
+    asset_type::AssetTypeService,
+    metrics::{MetricsService, RejectionReason},
+};
 use crate::{
+    constant::{
+        BALANCE_CHANGE_CHANNEL, CHAIN_VERIFY_BATCH_SIZE, EXPORT_CHANNEL_CAPACITY,
+        EXPORT_FETCH_SIZE, EXPORT_SEND_TIMEOUT, TRANSACTION_MAX_RETRIES, TRANSACTION_RETRY_DELAY,
+        TRANSFER_IN_ACTION_TYPE, TRANSFER_OUT_ACTION_TYPE,
+    },
     model::{
+        self,
         account::AccountModel,
-        account_log::AccountLogModel,
+        account_log::{
+            AccountActivityFilter, AccountBalance, AccountLogContext, AccountLogFilter,
+            AccountLogModel, BatchActionResult, OrderLogModel,
+        },
         action_type::{ActionTypeModel, Change},
+        client::ClientModel,
+        idempotency_key::IdempotencyKeyModel,
+        outbox::{BalanceChange, BalanceChangeEvent, EventType, OutboxEventModel},
+        transfer::TransferModel,
+        webhook::WebhookDeliveryModel,
+    },
+    request::{
+        AccountActionRequest, AccountActivityRequest, AccountBatchRequest, AccountLogExportRequest,
+        AccountLogRequest, AccountRequest, AccountReversalRequest, AccountTransferRequest,
+        AccountsRequest, ExportFormat, OrderLogRequest,
+    },
+    response::{
+        AccountActionsPreview, AccountActionsResponse, AccountActivity, AccountActivityResponse,
+        AccountLogsResponse, ActionPreview, BrokenLink, ChainBreak, ChainVerification,
+        PreviewFailure,
     },
-    request::{AccountActionRequest, AccountLogRequest, AccountRequest, AccountsRequest},
     utils,
 };
 use axum::http::StatusCode;
 use axum_kit::{AppResult, error::Error, postgres};
-use sqlx::types::Decimal;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgExecutor, PgPool, types::Decimal};
+use std::{collections::HashMap, io};
+use tokio::sync::mpsc::{self, error::SendTimeoutError};
 use validator::Validate;
 
 pub struct AccountService;
@@ -31,11 +61,15 @@ impl AccountService {
         action_type: &ActionTypeModel,
         account: &AccountModel,
         amount: Decimal,
+        record_rejection: bool,
     ) -> AppResult<()> {
         if (action_type.available_balance_change == Change::Dec
             && account.available_balance < amount)
             || (action_type.frozen_balance_change == Change::Dec && account.frozen_balance < amount)
         {
+            if record_rejection {
+                MetricsService::record_rejection(RejectionReason::InsufficientBalance);
+            }
             return Err(Error::Custom(
                 StatusCode::PAYMENT_REQUIRED,
                 "操作失败，存在余额不足的账户".to_string(),
@@ -47,12 +81,16 @@ impl AccountService {
     pub async fn check_balance_after_update(
         action_type: &ActionTypeModel,
         account: &AccountModel,
+        record_rejection: bool,
     ) -> AppResult<()> {
         if (action_type.available_balance_change == Change::Dec
             && account.available_balance.is_sign_negative())
             || (action_type.frozen_balance_change == Change::Dec
                 && account.frozen_balance.is_sign_negative())
         {
+            if record_rejection {
+                MetricsService::record_rejection(RejectionReason::InsufficientBalance);
+            }
             return Err(Error::Custom(
                 StatusCode::PAYMENT_REQUIRED,
                 "操作失败，存在余额不足的账户".to_string(),
@@ -61,14 +99,45 @@ impl AccountService {
         Ok(())
     }
 
+    // 客户端是否有权使用请求中的资产类型和操作类型
+    pub fn check_permission(
+        client: &ClientModel,
+        account_action_request: &AccountActionRequest,
+    ) -> AppResult<()> {
+        if !client.is_allowed(
+            account_action_request.asset_type_id,
+            account_action_request.action_type_id,
+        ) {
+            return Err(Error::Custom(
+                StatusCode::FORBIDDEN,
+                "操作失败，无权使用该资产类型或操作类型".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // 客户端是否有权访问该资产类型的账户
+    pub fn check_asset_permission(client: &ClientModel, asset_type_id: i32) -> AppResult<()> {
+        if !client.is_asset_allowed(asset_type_id) {
+            return Err(Error::Custom(
+                StatusCode::FORBIDDEN,
+                "操作失败，无权使用该资产类型".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     pub async fn check_account_log_exists(
+        executor: impl PgExecutor<'_>,
         account_id: i32,
         action_type_id: i32,
         order_number: &str,
+        record_rejection: bool,
     ) -> AppResult<()> {
-        if AccountLogModel::is_exists(postgres::conn(), account_id, action_type_id, order_number)
-            .await
-        {
+        if AccountLogModel::is_exists(executor, account_id, action_type_id, order_number).await {
+            if record_rejection {
+                MetricsService::record_rejection(RejectionReason::DuplicateOrder);
+            }
             return Err(Error::Custom(
                 StatusCode::CONFLICT,
                 "操作失败，存在已处理的订单".to_string(),
@@ -77,8 +146,12 @@ impl AccountService {
         Ok(())
     }
 
-    pub async fn create(account_request: &AccountRequest) -> AppResult<AccountModel> {
+    pub async fn create(
+        client: &ClientModel,
+        account_request: &AccountRequest,
+    ) -> AppResult<AccountModel> {
         account_request.validate()?;
+        Self::check_asset_permission(client, account_request.asset_type_id)?;
         let pool = postgres::conn();
         if AccountModel::is_exists(pool, account_request.user_id, account_request.asset_type_id)
             .await
@@ -94,8 +167,12 @@ impl AccountService {
         Ok(account)
     }
 
-    pub async fn info(account_request: &AccountRequest) -> AppResult<AccountModel> {
+    pub async fn info(
+        client: &ClientModel,
+        account_request: &AccountRequest,
+    ) -> AppResult<AccountModel> {
         account_request.validate()?;
+        Self::check_asset_permission(client, account_request.asset_type_id)?;
         let account = AccountModel::find(
             postgres::conn(),
             account_request.user_id,
@@ -105,57 +182,641 @@ impl AccountService {
         Ok(account)
     }
 
-    pub async fn infos(accounts_request: &AccountsRequest) -> AppResult<Vec<AccountModel>> {
-        let accounts = AccountModel::find_multiple(
-            postgres::conn(),
-            accounts_request.user_id,
-            AssetTypeService::ids(),
-        )
-        .await?;
+    // 只返回客户端被授权的资产类型的账户
+    pub async fn infos(
+        client: &ClientModel,
+        accounts_request: &AccountsRequest,
+    ) -> AppResult<Vec<AccountModel>> {
+        let asset_type_ids = AssetTypeService::ids()?
+            .into_iter()
+            .filter(|asset_type_id| client.is_asset_allowed(*asset_type_id))
+            .collect();
+        let accounts =
+            AccountModel::find_multiple(postgres::conn(), accounts_request.user_id, asset_type_ids)
+                .await?;
         Ok(accounts)
     }
 
-    pub async fn actions(account_action_requests: &Vec<AccountActionRequest>) -> AppResult<()> {
+    // 批量账户操作
+    // 以客户端id+`order_number`作为幂等键，与账户变更在同一事务内写入
+    // 同一请求重试时直接返回首次处理结果，订单号被其他请求占用时返回冲突
+    // 遇到死锁或序列化失败时自动重试整个事务
+    // 返回批次id及每个操作的记录id和操作后的余额
+    pub async fn actions(
+        client: &ClientModel,
+        account_action_requests: &Vec<AccountActionRequest>,
+    ) -> AppResult<AccountActionsResponse> {
+        Self::actions_with(postgres::conn(), client, account_action_requests).await
+    }
+
+    async fn actions_with(
+        pool: &PgPool,
+        client: &ClientModel,
+        account_action_requests: &Vec<AccountActionRequest>,
+    ) -> AppResult<AccountActionsResponse> {
         account_action_requests.validate()?;
-        let mut tx = postgres::conn().begin().await?;
         for account_action_request in account_action_requests {
-            let account = AccountModel::find_for_update(
+            Self::check_permission(client, account_action_request)?;
+        }
+        let request_hash = Self::request_hash(account_action_requests)?;
+        Self::retry_on_conflict(|| {
+            Self::try_actions(pool, client, account_action_requests, &request_hash)
+        })
+        .await
+    }
+
+    // 按实际执行的金额计算请求摘要，重试时金额写法不同（如`1`与`1.0`）不视为不同请求
+    fn request_hash(account_action_requests: &[AccountActionRequest]) -> AppResult<String> {
+        let canonical: Vec<_> = account_action_requests
+            .iter()
+            .map(|request| {
+                (
+                    request.user_id,
+                    request.asset_type_id,
+                    request.action_type_id,
+                    request.amount.abs().trunc_with_scale(6).normalize(),
+                    &request.order_number,
+                    &request.description,
+                )
+            })
+            .collect();
+        Ok(utils::sha256_hex(
+            serde_json::to_vec(&canonical).map_err(anyhow::Error::from)?,
+        ))
+    }
+
+    async fn try_actions(
+        pool: &PgPool,
+        client: &ClientModel,
+        account_action_requests: &[AccountActionRequest],
+        request_hash: &str,
+    ) -> AppResult<AccountActionsResponse> {
+        // 按订单号顺序写入幂等键，避免并发批次互相等待造成死锁
+        let mut order_numbers: Vec<&str> = account_action_requests
+            .iter()
+            .map(|request| request.order_number.as_str())
+            .collect();
+        order_numbers.sort_unstable();
+        order_numbers.dedup();
+        let mut tx = pool.begin().await?;
+        let batch_id = AccountLogModel::next_batch_id(&mut *tx).await?;
+        for order_number in order_numbers {
+            if IdempotencyKeyModel::create(
                 &mut *tx,
-                account_action_request.user_id,
-                account_action_request.asset_type_id,
+                client.id,
+                order_number,
+                request_hash,
+                batch_id,
+            )
+            .await?
+            {
+                continue;
+            }
+            let idempotency_key =
+                IdempotencyKeyModel::find(&mut *tx, client.id, order_number).await?;
+            if idempotency_key.request_hash == request_hash {
+                let results = match idempotency_key.batch_id {
+                    Some(batch_id) => {
+                        AccountLogModel::find_batch(&mut *tx, batch_id, client.id).await?
+                    }
+                    None => Vec::new(),
+                };
+                return Ok(AccountActionsResponse {
+                    batch_id: idempotency_key.batch_id,
+                    results,
+                });
+            }
+            MetricsService::record_rejection(RejectionReason::DuplicateOrder);
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，订单号已被其他请求使用".to_string(),
+            ));
+        }
+        // 之后仍按请求顺序执行操作
+        Self::lock_accounts(&mut tx, account_action_requests).await?;
+        let mut results = Vec::with_capacity(account_action_requests.len());
+        let mut changes = Vec::with_capacity(account_action_requests.len());
+        for account_action_request in account_action_requests {
+            let action_type = Self::action_type_by_id(account_action_request.action_type_id)?;
+            Self::check_before_update(&mut tx, account_action_request, &action_type).await?;
+            let account_log = Self::update_balance(
+                &mut tx,
+                account_action_request,
+                &action_type,
+                AccountLogContext {
+                    client_id: Some(client.id),
+                    batch_id: Some(batch_id),
+                    ..Default::default()
+                },
             )
             .await?;
-            if !account.is_active {
+            results.push(BatchActionResult::new(account_action_request, &account_log));
+            changes.push(BalanceChange::new(account_action_request, &account_log));
+        }
+        Self::commit(tx, EventType::AccountActions, Some(client.id), changes).await?;
+        Ok(AccountActionsResponse {
+            batch_id: Some(batch_id),
+            results,
+        })
+    }
+
+    // 按`(user_id, asset_type_id)`顺序锁定全部账户，避免并发批次互相等待造成死锁
+    // 不存在的账户跳过，由之后的检查报告
+    async fn lock_accounts(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        account_action_requests: &[AccountActionRequest],
+    ) -> AppResult<()> {
+        let mut accounts: Vec<(i32, i32)> = account_action_requests
+            .iter()
+            .map(|request| (request.user_id, request.asset_type_id))
+            .collect();
+        accounts.sort_unstable();
+        accounts.dedup();
+        for (user_id, asset_type_id) in accounts {
+            match AccountModel::find_for_update(&mut **tx, user_id, asset_type_id).await {
+                Ok(_) | Err(Error::Sqlx(sqlx::Error::RowNotFound)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    // 用户间转账
+    // 同一事务内扣减转出账户、增加转入账户，两条账户日志通过`transfer_id`关联
+    // 使用转账专用操作类型，不计入累计收入和累计支出，死锁时重试
+    pub async fn transfer(
+        client: &ClientModel,
+        account_transfer_request: &AccountTransferRequest,
+    ) -> AppResult<TransferModel> {
+        account_transfer_request.validate()?;
+        Self::retry_on_conflict(|| Self::try_transfer(client, account_transfer_request)).await
+    }
+
+    async fn try_transfer(
+        client: &ClientModel,
+        account_transfer_request: &AccountTransferRequest,
+    ) -> AppResult<TransferModel> {
+        let debit_action_type = Self::action_type_by_name(TRANSFER_OUT_ACTION_TYPE)?;
+        let credit_action_type = Self::action_type_by_name(TRANSFER_IN_ACTION_TYPE)?;
+        let debit_request = AccountActionRequest {
+            user_id: account_transfer_request.from_user_id,
+            asset_type_id: account_transfer_request.from_asset_type_id,
+            action_type_id: debit_action_type.id,
+            amount: account_transfer_request.amount,
+            order_number: account_transfer_request.order_number.clone(),
+            description: account_transfer_request.description.clone(),
+        };
+        let credit_request = AccountActionRequest {
+            user_id: account_transfer_request.to_user_id,
+            asset_type_id: account_transfer_request.to_asset_type_id,
+            action_type_id: credit_action_type.id,
+            amount: account_transfer_request.amount,
+            order_number: account_transfer_request.order_number.clone(),
+            description: account_transfer_request.description.clone(),
+        };
+        Self::check_permission(client, &debit_request)?;
+        Self::check_permission(client, &credit_request)?;
+        let mut tx = postgres::conn().begin().await?;
+        if TransferModel::is_exists(&mut *tx, &account_transfer_request.order_number).await {
+            return Err(Self::duplicate_transfer());
+        }
+        // 按`(user_id, asset_type_id)`顺序加锁，避免相向转账时互相等待造成死锁
+        let mut ordered = [
+            (&debit_request, &debit_action_type),
+            (&credit_request, &credit_action_type),
+        ];
+        ordered.sort_by_key(|(request, _)| (request.user_id, request.asset_type_id));
+        for (request, action_type) in ordered {
+            Self::check_before_update(&mut tx, request, action_type).await?;
+        }
+        let transfer = TransferModel::create(
+            &mut *tx,
+            account_transfer_request.from_user_id,
+            account_transfer_request.to_user_id,
+            account_transfer_request.from_asset_type_id,
+            account_transfer_request.amount.abs().trunc_with_scale(6),
+            account_transfer_request.order_number.as_ref(),
+            account_transfer_request.description.as_ref(),
+        )
+        .await?
+        .ok_or_else(Self::duplicate_transfer)?;
+        let context = AccountLogContext {
+            transfer_id: Some(transfer.id),
+            client_id: Some(client.id),
+            ..Default::default()
+        };
+        let debit_log =
+            Self::update_balance(&mut tx, &debit_request, &debit_action_type, context).await?;
+        let credit_log =
+            Self::update_balance(&mut tx, &credit_request, &credit_action_type, context).await?;
+        Self::commit(
+            tx,
+            EventType::AccountTransfer,
+            Some(client.id),
+            vec![
+                BalanceChange::new(&debit_request, &debit_log),
+                BalanceChange::new(&credit_request, &credit_log),
+            ],
+        )
+        .await?;
+        Ok(transfer)
+    }
+
+    fn duplicate_transfer() -> Error {
+        MetricsService::record_rejection(RejectionReason::DuplicateOrder);
+        Error::Custom(
+            StatusCode::CONFLICT,
+            "操作失败，存在已处理的订单".to_string(),
+        )
+    }
+
+    // 按订单号跨账户查询操作记录
+    pub async fn order_logs(order_log_request: &OrderLogRequest) -> AppResult<Vec<OrderLogModel>> {
+        order_log_request.validate()?;
+        AccountLogModel::find_by_order_number(
+            postgres::conn(),
+            &order_log_request.order_number,
+            order_log_request.month_range()?,
+        )
+        .await
+    }
+
+    // 按批次id查询客户端的批量操作结果
+    pub async fn batch(
+        client: &ClientModel,
+        account_batch_request: &AccountBatchRequest,
+    ) -> AppResult<AccountActionsResponse> {
+        account_batch_request.validate()?;
+        let results = AccountLogModel::find_batch(
+            postgres::conn(),
+            account_batch_request.batch_id,
+            client.id,
+        )
+        .await?;
+        if results.is_empty() {
+            return Err(Error::Custom(
+                StatusCode::NOT_FOUND,
+                "批次不存在".to_string(),
+            ));
+        }
+        Ok(AccountActionsResponse {
+            batch_id: Some(account_batch_request.batch_id),
+            results,
+        })
+    }
+
+    // 预览批量账户操作
+    // 在始终回滚的事务内执行与实际操作相同的检查和变更，返回每个操作变更后的余额
+    // 失败时返回已预览的操作及失败操作的序号和原因
+    pub async fn preview(
+        client: &ClientModel,
+        account_action_requests: &Vec<AccountActionRequest>,
+    ) -> AppResult<AccountActionsPreview> {
+        account_action_requests.validate()?;
+        Self::retry_on_conflict(|| Self::try_preview(client, account_action_requests)).await
+    }
+
+    async fn try_preview(
+        client: &ClientModel,
+        account_action_requests: &[AccountActionRequest],
+    ) -> AppResult<AccountActionsPreview> {
+        let mut tx = postgres::conn().begin().await?;
+        // 与实际操作相同，先按顺序锁定全部账户
+        Self::lock_accounts(&mut tx, account_action_requests).await?;
+        let mut results = Vec::with_capacity(account_action_requests.len());
+        for (index, account_action_request) in account_action_requests.iter().enumerate() {
+            match Self::preview_one(&mut tx, client, account_action_request).await {
+                Ok(account_log) => results.push(ActionPreview::new(
+                    index,
+                    account_action_request,
+                    &account_log,
+                )),
+                Err(Error::Custom(status, message)) => {
+                    tx.rollback().await?;
+                    return Ok(AccountActionsPreview {
+                        success: false,
+                        results,
+                        failure: Some(PreviewFailure {
+                            index,
+                            status: status.as_u16(),
+                            message,
+                        }),
+                    });
+                }
+                Err(Error::Sqlx(sqlx::Error::RowNotFound)) => {
+                    tx.rollback().await?;
+                    return Ok(AccountActionsPreview {
+                        success: false,
+                        results,
+                        failure: Some(PreviewFailure {
+                            index,
+                            status: StatusCode::NOT_FOUND.as_u16(),
+                            message: "操作失败，账户不存在".to_string(),
+                        }),
+                    });
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        tx.rollback().await?;
+        Ok(AccountActionsPreview {
+            success: true,
+            results,
+            failure: None,
+        })
+    }
+
+    async fn preview_one(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        client: &ClientModel,
+        account_action_request: &AccountActionRequest,
+    ) -> AppResult<AccountLogModel> {
+        Self::check_permission(client, account_action_request)?;
+        let action_type = Self::action_type_by_id(account_action_request.action_type_id)?;
+        Self::check_account_before_update(tx, account_action_request, &action_type, false).await?;
+        Self::apply_balance_change(
+            tx,
+            account_action_request,
+            &action_type,
+            AccountLogContext {
+                client_id: Some(client.id),
+                ..Default::default()
+            },
+            false,
+        )
+        .await
+    }
+
+    // 按订单号冲正已处理的账户操作
+    // 根据原日志的各金额字段推导反向操作类型，支持部分冲正，累计冲正金额不超过原操作金额
+    pub async fn reverse(
+        client: &ClientModel,
+        account_reversal_request: &AccountReversalRequest,
+    ) -> AppResult<Vec<AccountLogModel>> {
+        Self::reverse_with(postgres::conn(), client, account_reversal_request).await
+    }
+
+    async fn reverse_with(
+        pool: &PgPool,
+        client: &ClientModel,
+        account_reversal_request: &AccountReversalRequest,
+    ) -> AppResult<Vec<AccountLogModel>> {
+        account_reversal_request.validate()?;
+        let mut tx = pool.begin().await?;
+        // 先锁定账户，保证累计冲正金额的计算不受并发冲正影响
+        let account = AccountModel::find_for_update(
+            &mut *tx,
+            account_reversal_request.user_id,
+            account_reversal_request.asset_type_id,
+        )
+        .await?;
+        let original_logs = AccountLogModel::find_reversible(
+            &mut *tx,
+            account.id,
+            &account_reversal_request.order_number,
+            account_reversal_request.action_type_id,
+            client.id,
+        )
+        .await?;
+        if original_logs.is_empty() {
+            return Err(Error::Custom(
+                StatusCode::NOT_FOUND,
+                "操作失败，原操作记录不存在".to_string(),
+            ));
+        }
+        if account_reversal_request.amount.is_some() && original_logs.len() > 1 {
+            return Err(Error::Custom(
+                StatusCode::BAD_REQUEST,
+                "操作失败，存在多条原操作记录，部分冲正时请指定操作类型".to_string(),
+            ));
+        }
+        let mut reversal_logs = Vec::with_capacity(original_logs.len());
+        let mut changes = Vec::with_capacity(original_logs.len());
+        for original_log in original_logs {
+            let reversible_amount = original_log.amount()
+                - AccountLogModel::reversed_amount(&mut *tx, account.id, original_log.id).await?;
+            if reversible_amount <= Decimal::ZERO {
+                return Err(Error::Custom(
+                    StatusCode::CONFLICT,
+                    "操作失败，原操作已全部冲正".to_string(),
+                ));
+            }
+            let amount = match account_reversal_request.amount {
+                Some(amount) => amount.abs().trunc_with_scale(6),
+                None => reversible_amount,
+            };
+            if amount > reversible_amount {
                 return Err(Error::Custom(
-                    StatusCode::FORBIDDEN,
-                    "操作失败，存在未启用账户".to_string(),
+                    StatusCode::BAD_REQUEST,
+                    "操作失败，冲正金额超出原操作可冲正金额".to_string(),
                 ));
             }
-            let action_type =
-                ActionTypeService::by_id(account_action_request.action_type_id).unwrap();
-            Self::check_balance_before_update(
-                action_type,
-                &account,
-                account_action_request.amount.abs().trunc_with_scale(6),
+            let action_type = ActionTypeService::by_changes(
+                &Change::reverse_of(original_log.amount_available_balance),
+                &Change::reverse_of(original_log.amount_frozen_balance),
+                &Change::reverse_of(original_log.amount_total_income),
+                &Change::reverse_of(original_log.amount_total_expense),
+            )?
+            .ok_or_else(|| {
+                Error::Custom(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "操作失败，不存在可用的反向操作类型".to_string(),
+                )
+            })?;
+            let account_action_request = AccountActionRequest {
+                user_id: account_reversal_request.user_id,
+                asset_type_id: account_reversal_request.asset_type_id,
+                action_type_id: action_type.id,
+                amount,
+                order_number: account_reversal_request.reversal_order_number.clone(),
+                description: account_reversal_request.description.clone(),
+            };
+            Self::check_permission(client, &account_action_request)?;
+            Self::check_before_update(&mut tx, &account_action_request, &action_type).await?;
+            let reversal_log = Self::update_balance(
+                &mut tx,
+                &account_action_request,
+                &action_type,
+                AccountLogContext {
+                    reversed_log_id: Some(original_log.id),
+                    client_id: Some(client.id),
+                    ..Default::default()
+                },
             )
             .await?;
-            Self::check_account_log_exists(
-                account.id,
-                action_type.id,
-                account_action_request.order_number.as_str(),
+            changes.push(BalanceChange::new(&account_action_request, &reversal_log));
+            reversal_logs.push(reversal_log);
+        }
+        Self::commit(tx, EventType::AccountReverse, Some(client.id), changes).await?;
+        Ok(reversal_logs)
+    }
+
+    // 在余额变更的事务内写入事件及回调投递记录（由后台任务发布）后提交事务
+    // 提交成功后记录账户操作指标
+    pub async fn commit(
+        mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+        event_type: EventType,
+        client_id: Option<i32>,
+        changes: Vec<BalanceChange>,
+    ) -> AppResult<()> {
+        if !changes.is_empty() {
+            WebhookDeliveryModel::fan_out(&mut *tx, event_type, &changes).await?;
+            OutboxEventModel::create(
+                &mut *tx,
+                event_type,
+                &BalanceChangeEvent {
+                    client_id,
+                    changes: &changes,
+                },
             )
             .await?;
-            Self::update_balance(&mut tx, account_action_request, action_type).await?;
         }
         tx.commit().await?;
+        MetricsService::record_changes(&changes);
         Ok(())
     }
 
-    async fn update_balance(
+    // 死锁或序列化失败时重试，超过重试次数后返回冲突
+    async fn retry_on_conflict<T, F, Fut>(mut f: F) -> AppResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = AppResult<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            let error = match f().await {
+                Err(error) => error,
+                result => return result,
+            };
+            let Some(reason) = Self::conflict_reason(&error) else {
+                return Err(error);
+            };
+            if retries >= TRANSACTION_MAX_RETRIES {
+                tracing::warn!("事务重试次数已用完: {}", error);
+                return Err(Error::Custom(
+                    StatusCode::CONFLICT,
+                    "操作失败，账户繁忙，请稍后重试".to_string(),
+                ));
+            }
+            retries += 1;
+            MetricsService::record_transaction_retry(reason);
+            // 随机等待，避免冲突的事务同时重试
+            let delay =
+                TRANSACTION_RETRY_DELAY * retries + rand::random_range(0..TRANSACTION_RETRY_DELAY);
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+        }
+    }
+
+    fn conflict_reason(error: &Error) -> Option<&'static str> {
+        let Error::Sqlx(error) = error else {
+            return None;
+        };
+        match error.as_database_error()?.code()?.as_ref() {
+            "40P01" => Some("deadlock"),
+            "40001" => Some("serialization_failure"),
+            _ => None,
+        }
+    }
+
+    pub fn action_type_by_name(name: &str) -> AppResult<ActionTypeModel> {
+        ActionTypeService::by_name(name)?.ok_or_else(|| {
+            Error::Custom(
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("操作失败，操作类型{}未启用", name),
+            )
+        })
+    }
+
+    // 请求校验后操作类型可能已被停用，此时拒绝操作
+    fn action_type_by_id(id: i32) -> AppResult<ActionTypeModel> {
+        ActionTypeService::by_id(id)?.ok_or_else(|| {
+            Error::Custom(
+                StatusCode::BAD_REQUEST,
+                "操作失败，操作类型未启用".to_string(),
+            )
+        })
+    }
+
+    // 锁定账户并完成变更前的检查
+    pub async fn check_before_update(
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         account_action_request: &AccountActionRequest,
         action_type: &ActionTypeModel,
     ) -> AppResult<()> {
+        Self::check_account_before_update(tx, account_action_request, action_type, true).await
+    }
+
+    // 检查账户状态、余额及订单是否已处理，预览时不记录拒绝指标
+    async fn check_account_before_update(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        account_action_request: &AccountActionRequest,
+        action_type: &ActionTypeModel,
+        record_rejection: bool,
+    ) -> AppResult<()> {
+        let account =
+            Self::check_account_state(tx, account_action_request, action_type, record_rejection)
+                .await?;
+        Self::check_account_log_exists(
+            &mut **tx,
+            account.id,
+            action_type.id,
+            account_action_request.order_number.as_str(),
+            record_rejection,
+        )
+        .await?;
+        Ok(())
+    }
+
+    // 锁定账户并检查账户状态及余额，不检查订单是否已处理
+    // 由业务状态保证只执行一次的操作（如预授权释放）直接调用
+    pub async fn check_account_state(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        account_action_request: &AccountActionRequest,
+        action_type: &ActionTypeModel,
+        record_rejection: bool,
+    ) -> AppResult<AccountModel> {
+        let account = AccountModel::find_for_update(
+            &mut **tx,
+            account_action_request.user_id,
+            account_action_request.asset_type_id,
+        )
+        .await?;
+        if !account.is_active {
+            if record_rejection {
+                MetricsService::record_rejection(RejectionReason::InactiveAccount);
+            }
+            return Err(Error::Custom(
+                StatusCode::FORBIDDEN,
+                "操作失败，存在未启用账户".to_string(),
+            ));
+        }
+        Self::check_balance_before_update(
+            action_type,
+            &account,
+            account_action_request.amount.abs().trunc_with_scale(6),
+            record_rejection,
+        )
+        .await?;
+        Ok(account)
+    }
+
+    pub async fn update_balance(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        account_action_request: &AccountActionRequest,
+        action_type: &ActionTypeModel,
+        context: AccountLogContext,
+    ) -> AppResult<AccountLogModel> {
+        Self::apply_balance_change(tx, account_action_request, action_type, context, true).await
+    }
+
+    // 变更余额并写入账户操作记录，预览时不记录拒绝指标
+    async fn apply_balance_change(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        account_action_request: &AccountActionRequest,
+        action_type: &ActionTypeModel,
+        context: AccountLogContext,
+        record_rejection: bool,
+    ) -> AppResult<AccountLogModel> {
         let amount = &account_action_request.amount;
         let amount_available_balance = action_type
             .available_balance_change
@@ -176,9 +837,10 @@ impl AccountService {
         // 扣减`可用余额/冻结余额`时，不允许`可用余额/冻结余额`为负数
         // 增加`可用余额/冻结余额`时，允许`可用余额/冻结余额`为负数
         // 因为管理员可能直接操作数据库修改用户`可用余额/冻结余额`，所以只在扣减操作才判断
-        Self::check_balance_after_update(action_type, &account).await?;
-        AccountLogModel::create(
-            &mut **tx,
+        Self::check_balance_after_update(action_type, &account, record_rejection).await?;
+        AccountModel::notify(&mut **tx, BALANCE_CHANGE_CHANNEL, &account).await?;
+        let account_log = AccountLogModel::create(
+            tx,
             account.id,
             action_type.id,
             amount_available_balance,
@@ -191,13 +853,27 @@ impl AccountService {
             account.total_expense,
             account_action_request.order_number.as_ref(),
             account_action_request.description.as_ref(),
+            context,
         )
         .await?;
-        Ok(())
+        AccountModel::advance_chain(
+            &mut **tx,
+            account.id,
+            account_log.id,
+            account_log.hash.as_deref(),
+        )
+        .await?;
+        Ok(account_log)
     }
 
-    pub async fn logs(account_log_request: &AccountLogRequest) -> AppResult<Vec<AccountLogModel>> {
+    // 账户操作记录，同时返回统计、期初期末余额及下一页游标
+    // 传入游标时按游标定位，否则按`page`偏移，两种方式均返回游标
+    pub async fn logs(
+        client: &ClientModel,
+        account_log_request: &AccountLogRequest,
+    ) -> AppResult<AccountLogsResponse> {
         account_log_request.validate()?;
+        Self::check_asset_permission(client, account_log_request.asset_type_id)?;
         let account = AccountModel::find(
             postgres::conn(),
             account_log_request.user_id,
@@ -205,26 +881,615 @@ impl AccountService {
         )
         .await?;
 
-        let tz: chrono_tz::Tz = postgres::pg_session_timezone().parse().unwrap();
-
-        let account_logs = AccountLogModel::query_with_pagination(
-            postgres::conn(),
+        let cursor = account_log_request
+            .cursor
+            .as_deref()
+            .map(utils::decode_cursor)
+            .transpose()?;
+        let page_size = account_log_request.page_size as i64;
+        let offset = match cursor {
+            Some(_) => 0,
+            None => (account_log_request.page as i64 - 1) * page_size,
+        };
+        let filter = Self::log_filter(
             account.id,
             account_log_request.action_type_id,
-            account_log_request
-                .start_time
-                .as_ref()
-                .map(|s| utils::parse_day_boundary(s, tz, utils::DayBoundary::Start))
-                .transpose()?,
-            account_log_request
-                .end_time
-                .as_ref()
-                .map(|s| utils::parse_day_boundary(s, tz, utils::DayBoundary::End))
-                .transpose()?,
-            account_log_request.page,
-            account_log_request.page_size,
+            account_log_request.start_time.as_deref(),
+            account_log_request.end_time.as_deref(),
+        )?;
+        let pool = postgres::conn();
+        // 期初余额为开始时间前最后一条记录的变更后余额，未指定开始时间时从开户算起
+        let opening_balance = async {
+            match filter.start_time {
+                Some(start_time) => {
+                    AccountLogModel::balance_before(pool, account.id, start_time).await
+                }
+                None => Ok(None),
+            }
+        };
+        // 期末余额为结束时间及之前最后一条记录的变更后余额，未指定结束时间时为当前余额
+        let closing_balance = async {
+            match filter.end_time {
+                Some(end_time) => {
+                    AccountLogModel::balance_before(
+                        pool,
+                        account.id,
+                        end_time + Duration::microseconds(1),
+                    )
+                    .await
+                }
+                None => Ok(Some(AccountBalance {
+                    available_balance: account.available_balance,
+                    frozen_balance: account.frozen_balance,
+                    total_income: account.total_income,
+                    total_expense: account.total_expense,
+                })),
+            }
+        };
+        // 多取一条用于判断是否还有下一页
+        let account_logs =
+            AccountLogModel::query_with_pagination(pool, &filter, cursor, offset, page_size + 1);
+        let (mut account_logs, summary, opening_balance, closing_balance) = tokio::try_join!(
+            account_logs,
+            AccountLogModel::summarize(pool, &filter),
+            opening_balance,
+            closing_balance
+        )?;
+        let next_cursor = if account_logs.len() as i64 > page_size {
+            account_logs.truncate(page_size as usize);
+            account_logs
+                .last()
+                .map(|account_log| utils::encode_cursor(account_log.id))
+        } else {
+            None
+        };
+        Ok(AccountLogsResponse {
+            list: account_logs,
+            summary,
+            opening_balance: opening_balance.unwrap_or_default(),
+            closing_balance: closing_balance.unwrap_or_default(),
+            next_cursor,
+        })
+    }
+
+    // 用户全部资产账户的操作记录，按时间倒序合并
+    pub async fn activity(
+        client: &ClientModel,
+        account_activity_request: &AccountActivityRequest,
+    ) -> AppResult<AccountActivityResponse> {
+        Self::activity_with(postgres::conn(), client, account_activity_request).await
+    }
+
+    async fn activity_with(
+        pool: &PgPool,
+        client: &ClientModel,
+        account_activity_request: &AccountActivityRequest,
+    ) -> AppResult<AccountActivityResponse> {
+        account_activity_request.validate()?;
+        // 直接按客户端允许使用的资产类型查询账户，包括已停用资产类型的历史记录
+        let accounts = AccountModel::find_multiple(
+            pool,
+            account_activity_request.user_id,
+            client.asset_type_ids.clone(),
+        )
+        .await?;
+        if accounts.is_empty() {
+            return Ok(AccountActivityResponse {
+                list: Vec::new(),
+                next_cursor: None,
+            });
+        }
+        let asset_type_ids: HashMap<i32, i32> = accounts
+            .iter()
+            .map(|account| (account.id, account.asset_type_id))
+            .collect();
+
+        let cursor = account_activity_request
+            .cursor
+            .as_deref()
+            .map(utils::decode_cursor)
+            .transpose()?;
+        let page_size = account_activity_request.page_size as i64;
+        let offset = match cursor {
+            Some(_) => 0,
+            None => (account_activity_request.page as i64 - 1) * page_size,
+        };
+        let filter = AccountActivityFilter {
+            account_ids: accounts.iter().map(|account| account.id).collect(),
+            action_type_id: account_activity_request.action_type_id,
+            start_time: Self::log_time(
+                account_activity_request.start_time.as_deref(),
+                utils::DayBoundary::Start,
+            )?,
+            end_time: Self::log_time(
+                account_activity_request.end_time.as_deref(),
+                utils::DayBoundary::End,
+            )?,
+        };
+        // 多取一条用于判断是否还有下一页
+        let mut account_logs = AccountLogModel::query_activity_with_pagination(
+            pool,
+            &filter,
+            cursor,
+            offset,
+            page_size + 1,
         )
         .await?;
-        Ok(account_logs)
+        let next_cursor = if account_logs.len() as i64 > page_size {
+            account_logs.truncate(page_size as usize);
+            account_logs
+                .last()
+                .map(|account_log| utils::encode_cursor(account_log.id))
+        } else {
+            None
+        };
+        let list = account_logs
+            .into_iter()
+            .map(|account_log| AccountActivity {
+                asset_type_id: asset_type_ids[&account_log.account_id],
+                account_log,
+            })
+            .collect();
+        Ok(AccountActivityResponse { list, next_cursor })
+    }
+
+    // 导出账户操作记录
+    // 在独立任务中通过服务端游标分批读取，经有界通道逐块输出，内存占用与记录总数无关
+    pub async fn export(
+        client: &ClientModel,
+        account_log_export_request: &AccountLogExportRequest,
+    ) -> AppResult<mpsc::Receiver<io::Result<Vec<u8>>>> {
+        account_log_export_request.validate()?;
+        Self::check_asset_permission(client, account_log_export_request.asset_type_id)?;
+        let account = AccountModel::find(
+            postgres::conn(),
+            account_log_export_request.user_id,
+            account_log_export_request.asset_type_id,
+        )
+        .await?;
+        let filter = Self::log_filter(
+            account.id,
+            account_log_export_request.action_type_id,
+            account_log_export_request.start_time.as_deref(),
+            account_log_export_request.end_time.as_deref(),
+        )?;
+        let format = account_log_export_request.format;
+        let (sender, receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            if let Err(e) = Self::write_export(&filter, format, &sender).await {
+                tracing::error!(
+                    "导出账户操作记录失败: account_id={}, error={}",
+                    filter.account_id,
+                    e
+                );
+                // 中断响应体，避免客户端把不完整的导出当作完整文件
+                // 缓冲区已满时客户端未在读取，不再等待
+                let _ = sender.try_send(Err(io::Error::other(e.to_string())));
+            }
+        });
+        Ok(receiver)
+    }
+
+    async fn write_export(
+        filter: &AccountLogFilter,
+        format: ExportFormat,
+        sender: &mpsc::Sender<io::Result<Vec<u8>>>,
+    ) -> AppResult<()> {
+        // 游标仅在事务内有效
+        let mut tx = postgres::conn().begin().await?;
+        AccountLogModel::declare_export_cursor(&mut *tx, filter).await?;
+        let mut has_headers = true;
+        loop {
+            let rows = AccountLogModel::fetch_export_cursor(&mut *tx, EXPORT_FETCH_SIZE).await?;
+            if rows.is_empty() {
+                break;
+            }
+            let mut chunk = Vec::new();
+            match format {
+                ExportFormat::Csv => {
+                    let mut writer = csv::WriterBuilder::new()
+                        .has_headers(has_headers)
+                        .from_writer(&mut chunk);
+                    for row in rows {
+                        writer.serialize(row).map_err(anyhow::Error::from)?;
+                    }
+                    writer.flush().map_err(anyhow::Error::from)?;
+                }
+                ExportFormat::Ndjson => {
+                    for row in rows {
+                        serde_json::to_writer(&mut chunk, &row).map_err(anyhow::Error::from)?;
+                        chunk.push(b'\n');
+                    }
+                }
+            }
+            has_headers = false;
+            // 客户端长时间不读取时中断导出，避免一直占用事务及连接
+            match sender
+                .send_timeout(
+                    Ok(chunk),
+                    std::time::Duration::from_secs(EXPORT_SEND_TIMEOUT),
+                )
+                .await
+            {
+                Ok(()) => {}
+                // 客户端断开连接时停止导出
+                Err(SendTimeoutError::Closed(_)) => break,
+                Err(SendTimeoutError::Timeout(_)) => {
+                    return Err(anyhow::anyhow!("客户端读取超时").into());
+                }
+            }
+        }
+        tx.rollback().await?;
+        Ok(())
+    }
+
+    // 把请求中的日期转换为按会话时区计算的查询条件
+    fn log_filter(
+        account_id: i32,
+        action_type_id: Option<i32>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+    ) -> AppResult<AccountLogFilter> {
+        Ok(AccountLogFilter {
+            account_id,
+            action_type_id,
+            start_time: Self::log_time(start_time, utils::DayBoundary::Start)?,
+            end_time: Self::log_time(end_time, utils::DayBoundary::End)?,
+        })
+    }
+
+    // 按会话时区将日期转换为当天开始或结束时间
+    fn log_time(
+        date: Option<&str>,
+        boundary: utils::DayBoundary,
+    ) -> AppResult<Option<DateTime<Utc>>> {
+        Ok(date
+            .map(|s| {
+                let tz: chrono_tz::Tz = postgres::pg_session_timezone().parse().unwrap();
+                utils::parse_day_boundary(s, tz, boundary)
+            })
+            .transpose()?)
+    }
+
+    // 校验账户哈希链
+    // 按写入顺序逐条重新计算哈希，内容被修改时哈希不一致，记录被删除时后一条记录的上一条哈希不一致
+    // 哈希链从第一条带哈希的记录开始，以其保存的上一条哈希为起点，兼容迁移前的记录和已归档的分区
+    pub async fn verify_chain(account_request: &AccountRequest) -> AppResult<ChainVerification> {
+        Self::verify_chain_with(postgres::conn(), account_request).await
+    }
+
+    async fn verify_chain_with(
+        pool: &PgPool,
+        account_request: &AccountRequest,
+    ) -> AppResult<ChainVerification> {
+        account_request.validate()?;
+        let account =
+            AccountModel::find(pool, account_request.user_id, account_request.asset_type_id)
+                .await?;
+        let mut verification = ChainVerification {
+            account_id: account.id,
+            checked_logs: 0,
+            last_hash: None,
+            broken: None,
+        };
+        let mut tx = pool.begin().await?;
+        // 锚点与记录读取同一快照，避免校验期间新写入的记录造成误报
+        model::set_repeatable_read(&mut *tx).await?;
+        let anchor = AccountModel::chain_anchor(&mut *tx, account.id).await?;
+        // 从锚定的起始记录开始校验，之前的记录为迁移前或重新挂载的归档记录
+        let mut after_id = anchor.start_log_id.map_or(0, |id| id - 1);
+        let mut last_log = None;
+        'walk: loop {
+            let account_logs = AccountLogModel::fetch_chain(
+                &mut *tx,
+                account.id,
+                after_id,
+                CHAIN_VERIFY_BATCH_SIZE,
+            )
+            .await?;
+            let Some(last) = account_logs.last() else {
+                break;
+            };
+            after_id = last.id;
+            for account_log in account_logs {
+                let Some(hash) = account_log.hash.clone() else {
+                    if verification.last_hash.is_none() {
+                        continue;
+                    }
+                    verification.broken =
+                        Some(BrokenLink::new(ChainBreak::MissingHash, account_log));
+                    break 'walk;
+                };
+                if verification.last_hash.is_none() && anchor.start_log_id != Some(account_log.id) {
+                    verification.broken =
+                        Some(BrokenLink::new(ChainBreak::MissingHead, account_log));
+                    break 'walk;
+                }
+                verification.checked_logs += 1;
+                if verification.last_hash.is_some()
+                    && account_log.prev_hash != verification.last_hash
+                {
+                    verification.broken =
+                        Some(BrokenLink::new(ChainBreak::PrevHashMismatch, account_log));
+                    break 'walk;
+                }
+                if account_log.compute_hash(account_log.prev_hash.as_deref())? != hash {
+                    verification.broken =
+                        Some(BrokenLink::new(ChainBreak::HashMismatch, account_log));
+                    break 'walk;
+                }
+                verification.last_hash = Some(hash);
+                last_log = Some(account_log);
+            }
+        }
+        tx.commit().await?;
+        // 全部记录均已归档时没有起始锚点，无需比对
+        if verification.broken.is_none() && anchor.start_log_id.is_some() {
+            verification.broken = match last_log {
+                None => Some(BrokenLink::without_log(ChainBreak::MissingHead)),
+                Some(log) if verification.last_hash != anchor.last_log_hash => {
+                    Some(BrokenLink::new(ChainBreak::TailMismatch, log))
+                }
+                Some(_) => None,
+            };
+        }
+        Ok(verification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constant::{MIN_PAGE, MIN_PAGE_SIZE},
+        service::asset_type::AssetTypeService,
+    };
+
+    const USER_ID: i32 = 1;
+    const ASSET_TYPE_ID: i32 = 1;
+
+    // 订单号至少32位
+    fn order_number(tag: &str) -> String {
+        format!("{tag:-<32}")
+    }
+
+    // 加载缓存，创建允许使用`action_types`的客户端及`user_id`在`asset_type_ids`下的账户
+    async fn setup(pool: &PgPool, asset_type_ids: &[i32], action_types: &[&str]) -> ClientModel {
+        AssetTypeService::reload_with(pool).await.unwrap();
+        ActionTypeService::reload_with(pool).await.unwrap();
+        let action_type_ids: Vec<i32> = action_types
+            .iter()
+            .map(|name| AccountService::action_type_by_name(name).unwrap().id)
+            .collect();
+        let client = ClientModel::create(
+            pool,
+            "account-test",
+            "",
+            asset_type_ids,
+            &action_type_ids,
+            None,
+        )
+        .await
+        .unwrap();
+        for &asset_type_id in asset_type_ids {
+            AccountModel::create(pool, USER_ID, asset_type_id)
+                .await
+                .unwrap();
+        }
+        client
+    }
+
+    fn action(
+        asset_type_id: i32,
+        action_type: &str,
+        amount: i64,
+        tag: &str,
+    ) -> AccountActionRequest {
+        AccountActionRequest {
+            user_id: USER_ID,
+            asset_type_id,
+            action_type_id: AccountService::action_type_by_name(action_type).unwrap().id,
+            amount: Decimal::from(amount),
+            order_number: order_number(tag),
+            description: "测试".to_string(),
+        }
+    }
+
+    async fn available_balance(pool: &PgPool, asset_type_id: i32) -> Decimal {
+        AccountModel::find(pool, USER_ID, asset_type_id)
+            .await
+            .unwrap()
+            .available_balance
+    }
+
+    fn reversal(amount: Option<i64>, tag: &str) -> AccountReversalRequest {
+        AccountReversalRequest {
+            user_id: USER_ID,
+            asset_type_id: ASSET_TYPE_ID,
+            order_number: order_number("deposit"),
+            action_type_id: None,
+            amount: amount.map(Decimal::from),
+            reversal_order_number: order_number(tag),
+            description: "冲正".to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn actions_replay_returns_first_result(pool: PgPool) {
+        let client = setup(&pool, &[ASSET_TYPE_ID], &["AB_INC"]).await;
+        let requests = vec![action(ASSET_TYPE_ID, "AB_INC", 100, "deposit")];
+        let first = AccountService::actions_with(&pool, &client, &requests)
+            .await
+            .unwrap();
+        // 同一请求重试时返回首次处理结果，不重复入账
+        let replay = AccountService::actions_with(&pool, &client, &requests)
+            .await
+            .unwrap();
+        assert_eq!(replay.batch_id, first.batch_id);
+        assert_eq!(replay.results.len(), 1);
+        assert_eq!(replay.results[0].log_id, first.results[0].log_id);
+        assert_eq!(
+            available_balance(&pool, ASSET_TYPE_ID).await,
+            Decimal::from(100)
+        );
+    }
+
+    #[sqlx::test]
+    async fn actions_reject_reused_order_number(pool: PgPool) {
+        let client = setup(&pool, &[ASSET_TYPE_ID], &["AB_INC"]).await;
+        let requests = vec![action(ASSET_TYPE_ID, "AB_INC", 100, "deposit")];
+        AccountService::actions_with(&pool, &client, &requests)
+            .await
+            .unwrap();
+        // 相同订单号、不同内容的请求返回冲突
+        let requests = vec![action(ASSET_TYPE_ID, "AB_INC", 50, "deposit")];
+        let result = AccountService::actions_with(&pool, &client, &requests).await;
+        assert!(matches!(
+            result,
+            Err(Error::Custom(StatusCode::CONFLICT, _))
+        ));
+        assert_eq!(
+            available_balance(&pool, ASSET_TYPE_ID).await,
+            Decimal::from(100)
+        );
+    }
+
+    #[sqlx::test]
+    async fn reverse_partially_then_rest(pool: PgPool) {
+        let client = setup(&pool, &[ASSET_TYPE_ID], &["AB_INC", "AB_INC_RTN"]).await;
+        let requests = vec![action(ASSET_TYPE_ID, "AB_INC", 100, "deposit")];
+        AccountService::actions_with(&pool, &client, &requests)
+            .await
+            .unwrap();
+
+        let logs = AccountService::reverse_with(&pool, &client, &reversal(Some(30), "reverse-1"))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].amount_available_balance, Decimal::from(-30));
+        assert_eq!(
+            available_balance(&pool, ASSET_TYPE_ID).await,
+            Decimal::from(70)
+        );
+        // 未指定金额时冲正剩余可冲正金额
+        let logs = AccountService::reverse_with(&pool, &client, &reversal(None, "reverse-2"))
+            .await
+            .unwrap();
+        assert_eq!(logs[0].amount_available_balance, Decimal::from(-70));
+        assert_eq!(available_balance(&pool, ASSET_TYPE_ID).await, Decimal::ZERO);
+        // 已全部冲正后不能再冲正
+        let result =
+            AccountService::reverse_with(&pool, &client, &reversal(Some(1), "reverse-3")).await;
+        assert!(matches!(
+            result,
+            Err(Error::Custom(StatusCode::CONFLICT, _))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn reverse_rejects_amount_over_reversible(pool: PgPool) {
+        let client = setup(&pool, &[ASSET_TYPE_ID], &["AB_INC", "AB_INC_RTN"]).await;
+        let requests = vec![action(ASSET_TYPE_ID, "AB_INC", 100, "deposit")];
+        AccountService::actions_with(&pool, &client, &requests)
+            .await
+            .unwrap();
+        AccountService::reverse_with(&pool, &client, &reversal(Some(60), "reverse-1"))
+            .await
+            .unwrap();
+        // 超出剩余可冲正金额的冲正被拒绝，余额不变
+        let result =
+            AccountService::reverse_with(&pool, &client, &reversal(Some(41), "reverse-2")).await;
+        assert!(matches!(
+            result,
+            Err(Error::Custom(StatusCode::BAD_REQUEST, _))
+        ));
+        assert_eq!(
+            available_balance(&pool, ASSET_TYPE_ID).await,
+            Decimal::from(40)
+        );
+    }
+
+    #[sqlx::test]
+    async fn verify_chain_detects_tampered_log(pool: PgPool) {
+        let client = setup(&pool, &[ASSET_TYPE_ID], &["AB_INC"]).await;
+        let mut log_ids = Vec::new();
+        for tag in ["deposit-1", "deposit-2", "deposit-3"] {
+            let requests = vec![action(ASSET_TYPE_ID, "AB_INC", 10, tag)];
+            let response = AccountService::actions_with(&pool, &client, &requests)
+                .await
+                .unwrap();
+            log_ids.push(response.results[0].log_id);
+        }
+        let account_request = AccountRequest {
+            user_id: USER_ID,
+            asset_type_id: ASSET_TYPE_ID,
+        };
+        let verification = AccountService::verify_chain_with(&pool, &account_request)
+            .await
+            .unwrap();
+        assert_eq!(verification.checked_logs, 3);
+        assert!(verification.broken.is_none());
+
+        // 修改中间一条记录的金额
+        sqlx::query!(
+            r#"update account_log
+                set amount_available_balance = 1000
+            where
+                id = $1"#,
+            log_ids[1]
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let verification = AccountService::verify_chain_with(&pool, &account_request)
+            .await
+            .unwrap();
+        let broken = verification.broken.unwrap();
+        assert_eq!(broken.reason, ChainBreak::HashMismatch);
+        assert_eq!(broken.log_id, Some(log_ids[1]));
+    }
+
+    #[sqlx::test]
+    async fn activity_pages_across_accounts(pool: PgPool) {
+        let client = setup(&pool, &[1, 2], &["AB_INC"]).await;
+        let mut log_ids = Vec::new();
+        // 两个账户交替入账，共三页，最后一页不满
+        for i in 0..12 {
+            let asset_type_id = i % 2 + 1;
+            let requests = vec![action(asset_type_id, "AB_INC", 10, &format!("deposit-{i}"))];
+            let response = AccountService::actions_with(&pool, &client, &requests)
+                .await
+                .unwrap();
+            log_ids.push((response.results[0].log_id, asset_type_id));
+        }
+        log_ids.reverse();
+
+        let mut request = AccountActivityRequest {
+            user_id: USER_ID,
+            action_type_id: None,
+            start_time: None,
+            end_time: None,
+            page: MIN_PAGE,
+            page_size: MIN_PAGE_SIZE,
+            cursor: None,
+        };
+        let mut activities = Vec::new();
+        loop {
+            let response = AccountService::activity_with(&pool, &client, &request)
+                .await
+                .unwrap();
+            assert!(response.list.len() <= MIN_PAGE_SIZE as usize);
+            activities.extend(
+                response
+                    .list
+                    .into_iter()
+                    .map(|activity| (activity.account_log.id, activity.asset_type_id)),
+            );
+            match response.next_cursor {
+                Some(cursor) => request.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(activities, log_ids);
     }
 }
@@ -1,33 +1,214 @@
-use crate::model::action_type::ActionTypeModel;
-use axum_kit::{AppResult, postgres};
-use std::sync::OnceLock;
+use crate::{
+    constant::BUILTIN_ACTION_TYPES,
+    model::action_type::{ActionTypeModel, Change},
+    request::{ActionTypeActiveRequest, ActionTypeCreateRequest, ActionTypeUpdateRequest},
+};
+use axum::http::StatusCode;
+use axum_kit::{AppResult, error::Error, postgres};
+use sqlx::PgPool;
+use std::sync::{Arc, RwLock};
+use validator::Validate;
 
-static ACTION_TYPE: OnceLock<Vec<ActionTypeModel>> = OnceLock::new();
+static ACTION_TYPE: RwLock<Option<Arc<Vec<ActionTypeModel>>>> = RwLock::new(None);
 
 pub struct ActionTypeService;
 
 impl ActionTypeService {
     pub async fn init() -> AppResult<()> {
-        let action_types = ActionTypeModel::fetch_all(postgres::conn()).await?;
-        let _ = ACTION_TYPE
-            .set(action_types)
-            .map_err(|_| "Failed to initialize ACTION_TYPE");
+        Self::reload().await
+    }
+
+    // 从数据库重新加载，替换整个缓存
+    pub async fn reload() -> AppResult<()> {
+        Self::reload_with(postgres::conn()).await
+    }
+
+    pub async fn reload_with(pool: &PgPool) -> AppResult<()> {
+        let action_types = ActionTypeModel::fetch_all(pool).await?;
+        *ACTION_TYPE.write().unwrap() = Some(Arc::new(action_types));
         Ok(())
     }
 
-    pub fn list() -> &'static Vec<ActionTypeModel> {
-        ACTION_TYPE.get().expect("ACTION_TYPE is not initialized")
+    // 缓存是否已加载
+    pub fn is_initialized() -> bool {
+        ACTION_TYPE.read().unwrap().is_some()
+    }
+
+    // 全部操作类型，包括已停用的，用于查询历史记录
+    // 缓存未加载时返回503，由后台任务重试加载
+    pub fn list_all() -> AppResult<Arc<Vec<ActionTypeModel>>> {
+        ACTION_TYPE.read().unwrap().clone().ok_or_else(|| {
+            Error::Custom(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "操作类型缓存未加载".to_string(),
+            )
+        })
+    }
+
+    // 已启用的操作类型
+    pub fn list() -> AppResult<Vec<ActionTypeModel>> {
+        let action_types = Self::list_all()?;
+        Ok(action_types
+            .iter()
+            .filter(|&action_type| action_type.is_active)
+            .cloned()
+            .collect())
+    }
+
+    pub fn is_active(id: i32) -> AppResult<bool> {
+        let action_types = Self::list_all()?;
+        Ok(action_types
+            .iter()
+            .any(|action_type| action_type.id == id && action_type.is_active))
+    }
+
+    // 操作类型是否存在，包括已停用的
+    pub fn is_exists(id: i32) -> AppResult<bool> {
+        let action_types = Self::list_all()?;
+        Ok(action_types.iter().any(|action_type| action_type.id == id))
     }
 
-    pub fn is_active(id: i32) -> bool {
-        let action_types = Self::list();
-        action_types.iter().any(|action_type| action_type.id == id)
+    pub fn by_id(id: i32) -> AppResult<Option<ActionTypeModel>> {
+        let action_types = Self::list_all()?;
+        Ok(action_types
+            .iter()
+            .find(|&action_type| action_type.id == id && action_type.is_active)
+            .cloned())
+    }
+
+    pub fn by_name(name: &str) -> AppResult<Option<ActionTypeModel>> {
+        let action_types = Self::list_all()?;
+        Ok(action_types
+            .iter()
+            .find(|&action_type| action_type.name == name && action_type.is_active)
+            .cloned())
     }
 
-    pub fn by_id(id: i32) -> Option<&'static ActionTypeModel> {
-        let action_types = Self::list();
-        action_types
+    // 按四个字段的变化方向查找已启用的操作类型
+    pub fn by_changes(
+        available_balance_change: &Change,
+        frozen_balance_change: &Change,
+        total_income_change: &Change,
+        total_expense_change: &Change,
+    ) -> AppResult<Option<ActionTypeModel>> {
+        let action_types = Self::list_all()?;
+        Ok(action_types
             .iter()
-            .find(|&action_type| action_type.id == id)
+            .find(|&action_type| {
+                action_type.is_active
+                    && &action_type.available_balance_change == available_balance_change
+                    && &action_type.frozen_balance_change == frozen_balance_change
+                    && &action_type.total_income_change == total_income_change
+                    && &action_type.total_expense_change == total_expense_change
+            })
+            .cloned())
+    }
+
+    pub async fn create(
+        action_type_create_request: &ActionTypeCreateRequest,
+    ) -> AppResult<ActionTypeModel> {
+        action_type_create_request.validate()?;
+        Self::check_name_unique(&action_type_create_request.name, None).await?;
+        let action_type = ActionTypeModel::create(
+            postgres::conn(),
+            &action_type_create_request.name,
+            &action_type_create_request.description,
+            action_type_create_request.available_balance_change.clone(),
+            action_type_create_request.frozen_balance_change.clone(),
+            action_type_create_request.total_income_change.clone(),
+            action_type_create_request.total_expense_change.clone(),
+            action_type_create_request.is_active,
+        )
+        .await?;
+        Self::reload().await?;
+        Ok(action_type)
+    }
+
+    pub async fn update(
+        action_type_update_request: &ActionTypeUpdateRequest,
+    ) -> AppResult<ActionTypeModel> {
+        action_type_update_request.validate()?;
+        let action_type = Self::find(action_type_update_request.id).await?;
+        // 内置操作类型只允许修改描述
+        if action_type_update_request.name.is_some()
+            || action_type_update_request
+                .available_balance_change
+                .is_some()
+            || action_type_update_request.frozen_balance_change.is_some()
+            || action_type_update_request.total_income_change.is_some()
+            || action_type_update_request.total_expense_change.is_some()
+        {
+            Self::check_not_builtin(&action_type)?;
+        }
+        if let Some(name) = &action_type_update_request.name {
+            Self::check_name_unique(name, Some(action_type_update_request.id)).await?;
+        }
+        let action_type = ActionTypeModel::update(
+            postgres::conn(),
+            action_type_update_request.id,
+            action_type_update_request.name.as_deref(),
+            action_type_update_request.description.as_deref(),
+            action_type_update_request.available_balance_change.clone(),
+            action_type_update_request.frozen_balance_change.clone(),
+            action_type_update_request.total_income_change.clone(),
+            action_type_update_request.total_expense_change.clone(),
+        )
+        .await?;
+        Self::reload().await?;
+        Ok(action_type)
+    }
+
+    pub async fn activate(
+        action_type_active_request: &ActionTypeActiveRequest,
+    ) -> AppResult<ActionTypeModel> {
+        Self::set_active(action_type_active_request, true).await
+    }
+
+    pub async fn deactivate(
+        action_type_active_request: &ActionTypeActiveRequest,
+    ) -> AppResult<ActionTypeModel> {
+        Self::set_active(action_type_active_request, false).await
+    }
+
+    async fn set_active(
+        action_type_active_request: &ActionTypeActiveRequest,
+        is_active: bool,
+    ) -> AppResult<ActionTypeModel> {
+        action_type_active_request.validate()?;
+        let action_type = Self::find(action_type_active_request.id).await?;
+        if !is_active {
+            Self::check_not_builtin(&action_type)?;
+        }
+        let action_type =
+            ActionTypeModel::set_active(postgres::conn(), action_type_active_request.id, is_active)
+                .await?;
+        Self::reload().await?;
+        Ok(action_type)
+    }
+
+    async fn find(id: i32) -> AppResult<ActionTypeModel> {
+        ActionTypeModel::find(postgres::conn(), id)
+            .await?
+            .ok_or_else(|| Error::Custom(StatusCode::NOT_FOUND, "操作类型不存在".to_string()))
+    }
+
+    fn check_not_builtin(action_type: &ActionTypeModel) -> AppResult<()> {
+        if BUILTIN_ACTION_TYPES.contains(&action_type.name.as_str()) {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，内置操作类型不允许修改".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn check_name_unique(name: &str, exclude_id: Option<i32>) -> AppResult<()> {
+        if ActionTypeModel::is_name_exists(postgres::conn(), name, exclude_id).await {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，操作类型名称已存在".to_string(),
+            ));
+        }
+        Ok(())
     }
 }
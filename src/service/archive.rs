@@ -0,0 +1,313 @@
+use crate::{
+    constant::{
+        ARCHIVE_CHANNEL_CAPACITY, ARCHIVE_CHUNK_SIZE, ARCHIVE_DEFAULT_DIR, ARCHIVE_DIR_ENV,
+        ARCHIVE_INTERVAL, ARCHIVE_LOCK_NAME, ARCHIVE_LOCK_TIMEOUT, ARCHIVE_RETENTION_MONTHS,
+    },
+    model::{self, account::AccountModel, archive::ArchiveManifest, partition::PartitionModel},
+    request::ArchiveMonthRequest,
+    utils,
+};
+use axum::http::StatusCode;
+use axum_kit::{AppResult, error::Error, postgres};
+use chrono::{Datelike, Months, NaiveDate, TimeZone};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use sqlx::types::chrono::Utc;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use validator::Validate;
+
+pub struct ArchiveService;
+
+impl ArchiveService {
+    // 归档超出保留期的分区：导出为压缩CSV并生成清单，再卸载并删除分区
+    // 已归档后重新挂载的分区不会再次归档，需通过`unload`卸载
+    pub async fn run() -> AppResult<Vec<ArchiveManifest>> {
+        let dir = Self::dir().await?;
+        // 持有咨询锁直至本次归档结束，多实例部署时只有一个实例执行
+        let mut lock = postgres::conn().begin().await?;
+        if !model::try_advisory_xact_lock(&mut *lock, ARCHIVE_LOCK_NAME).await? {
+            return Ok(Vec::new());
+        }
+        let today = Utc::now().date_naive();
+        let cutoff = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+            .and_then(|month| month.checked_sub_months(Months::new(ARCHIVE_RETENTION_MONTHS)))
+            .ok_or_else(|| anyhow::anyhow!("计算归档截止月份失败"))?;
+        let partitions = PartitionModel::fetch_all(postgres::conn()).await?;
+        let mut manifests = Vec::new();
+        // 上次归档在分区删除提交后、清单确认前中断，补充确认清单
+        // 分区仍挂载时说明删除未提交，下面重新归档并覆盖待确认清单
+        let pending = Self::blocking({
+            let dir = dir.clone();
+            move || ArchiveManifest::pending(&dir)
+        })
+        .await?;
+        for partition in pending {
+            if !partitions.iter().any(|p| p.name == partition) {
+                let dir = dir.clone();
+                manifests.push(
+                    Self::blocking(move || ArchiveManifest::confirm(&dir, &partition)).await?,
+                );
+            }
+        }
+        for partition in partitions {
+            let Some(month) = Self::parse_partition_month(&partition.name) else {
+                continue;
+            };
+            if month >= cutoff || Self::load_manifest(&dir, &partition.name).await?.is_some() {
+                continue;
+            }
+            manifests.push(Self::archive(&dir, &partition.name, month).await?);
+        }
+        lock.commit().await?;
+        Ok(manifests)
+    }
+
+    // 定时归档
+    pub async fn run_task() {
+        let mut interval = tokio::time::interval(Duration::from_secs(ARCHIVE_INTERVAL));
+        loop {
+            interval.tick().await;
+            match Self::run().await {
+                Ok(manifests) => {
+                    for manifest in manifests {
+                        tracing::info!(
+                            "分区已归档: partition={}, rows={}, file={}",
+                            manifest.partition,
+                            manifest.rows,
+                            manifest.file
+                        );
+                    }
+                }
+                Err(e) => tracing::error!("分区归档失败: {}", e),
+            }
+        }
+    }
+
+    pub async fn list() -> AppResult<Vec<ArchiveManifest>> {
+        let dir = Self::dir().await?;
+        Self::blocking(move || ArchiveManifest::list(&dir)).await
+    }
+
+    // 重新挂载已归档的月份，用于调查历史记录
+    // 导入前校验归档文件摘要，导入后校验行数
+    pub async fn restore(
+        archive_month_request: &ArchiveMonthRequest,
+    ) -> AppResult<ArchiveManifest> {
+        archive_month_request.validate()?;
+        let dir = Self::dir().await?;
+        let partition = Self::partition_name(archive_month_request.month()?);
+        let manifest = Self::find_manifest(&dir, &partition).await?;
+        if Self::is_attached(&partition).await? {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "恢复失败，该月份分区已挂载".to_string(),
+            ));
+        }
+        let path = dir.join(&manifest.file);
+        let sha256 = Self::blocking({
+            let path = path.clone();
+            move || Ok(utils::sha256_file(&path)?)
+        })
+        .await?;
+        if sha256 != manifest.sha256 {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "恢复失败，归档文件校验不一致".to_string(),
+            ));
+        }
+        let mut tx = postgres::conn().begin().await?;
+        PartitionModel::create_detached(&mut *tx, &partition).await?;
+        let (sender, receiver) = mpsc::channel(ARCHIVE_CHANNEL_CAPACITY);
+        let reader = tokio::task::spawn_blocking(move || Self::read_gzip(&path, sender));
+        let copied =
+            PartitionModel::copy_in(&mut tx, &partition, &manifest.columns, receiver).await;
+        reader.await.map_err(anyhow::Error::from)?;
+        let rows = copied?;
+        if rows as i64 != manifest.rows {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                format!(
+                    "恢复失败，导入行数{}与归档清单行数{}不一致",
+                    rows, manifest.rows
+                ),
+            ));
+        }
+        PartitionModel::attach(
+            &mut *tx,
+            &partition,
+            manifest.range_start,
+            manifest.range_end,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(manifest)
+    }
+
+    // 卸载并删除重新挂载的已归档分区
+    // 分区行数须与归档清单一致，避免删除归档后写入的记录
+    pub async fn unload(archive_month_request: &ArchiveMonthRequest) -> AppResult<ArchiveManifest> {
+        archive_month_request.validate()?;
+        let dir = Self::dir().await?;
+        let partition = Self::partition_name(archive_month_request.month()?);
+        let manifest = Self::find_manifest(&dir, &partition).await?;
+        if !Self::is_attached(&partition).await? {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "卸载失败，该月份分区未挂载".to_string(),
+            ));
+        }
+        let mut tx = postgres::conn().begin().await?;
+        PartitionModel::set_lock_timeout(&mut *tx, ARCHIVE_LOCK_TIMEOUT).await?;
+        PartitionModel::lock_for_export(&mut *tx, &partition).await?;
+        if PartitionModel::count_rows(&mut *tx, &partition).await? != manifest.rows {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "卸载失败，分区行数与归档清单不一致".to_string(),
+            ));
+        }
+        PartitionModel::detach_and_drop(&mut tx, &partition).await?;
+        tx.commit().await?;
+        Ok(manifest)
+    }
+
+    async fn archive(dir: &Path, partition: &str, month: NaiveDate) -> AppResult<ArchiveManifest> {
+        let range_start = Utc.from_utc_datetime(&month.and_time(Default::default()));
+        let range_end = range_start
+            .checked_add_months(Months::new(1))
+            .ok_or_else(|| anyhow::anyhow!("计算分区范围失败"))?;
+        let file = format!("{partition}.csv.gz");
+        let path = dir.join(&file);
+        let temp_path = dir.join(format!("{file}.tmp"));
+        let mut tx = postgres::conn().begin().await?;
+        PartitionModel::set_lock_timeout(&mut *tx, ARCHIVE_LOCK_TIMEOUT).await?;
+        // 导出期间阻止写入，保证文件内容与行数一致
+        PartitionModel::lock_for_export(&mut *tx, partition).await?;
+        let rows = PartitionModel::count_rows(&mut *tx, partition).await?;
+        let columns = PartitionModel::columns(&mut *tx, partition).await?;
+        let (sender, receiver) = mpsc::channel(ARCHIVE_CHANNEL_CAPACITY);
+        let writer = tokio::task::spawn_blocking({
+            let temp_path = temp_path.clone();
+            move || Self::write_gzip(&temp_path, receiver)
+        });
+        let copied = PartitionModel::copy_out(&mut tx, partition, &sender).await;
+        drop(sender);
+        writer.await.map_err(anyhow::Error::from)??;
+        copied?;
+        let sha256 = Self::blocking(move || {
+            fs::rename(&temp_path, &path).map_err(anyhow::Error::from)?;
+            Ok(utils::sha256_file(&path)?)
+        })
+        .await?;
+        let manifest = ArchiveManifest {
+            partition: partition.to_string(),
+            range_start,
+            range_end,
+            rows,
+            file,
+            sha256,
+            columns,
+            archived_at: Utc::now(),
+        };
+        // 清单写入成功后才删除分区，删除提交后再确认清单
+        let dir = dir.to_path_buf();
+        Self::blocking({
+            let dir = dir.clone();
+            move || manifest.save_pending(&dir)
+        })
+        .await?;
+        PartitionModel::detach_and_drop(&mut tx, partition).await?;
+        AccountModel::reset_chain_start(&mut *tx).await?;
+        tx.commit().await?;
+        let partition = partition.to_string();
+        Self::blocking(move || ArchiveManifest::confirm(&dir, &partition)).await
+    }
+
+    // 在阻塞线程中压缩写入归档文件，直到导出结束
+    fn write_gzip(path: &Path, mut receiver: mpsc::Receiver<Vec<u8>>) -> AppResult<()> {
+        let mut encoder = GzEncoder::new(
+            BufWriter::new(File::create(path).map_err(anyhow::Error::from)?),
+            Compression::default(),
+        );
+        while let Some(chunk) = receiver.blocking_recv() {
+            encoder.write_all(&chunk).map_err(anyhow::Error::from)?;
+        }
+        encoder
+            .finish()
+            .map_err(anyhow::Error::from)?
+            .into_inner()
+            .map_err(|e| anyhow::Error::from(e.into_error()))?
+            .sync_all()
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    // 在阻塞线程中解压归档文件并按数据块发送，出错时发送错误后结束
+    fn read_gzip(path: &Path, sender: mpsc::Sender<io::Result<Vec<u8>>>) {
+        let result = File::open(path).and_then(|file| {
+            let mut reader = GzDecoder::new(BufReader::new(file));
+            let mut buffer = vec![0; ARCHIVE_CHUNK_SIZE];
+            loop {
+                let size = reader.read(&mut buffer)?;
+                // 导入已结束或中止时不再读取
+                if size == 0 || sender.blocking_send(Ok(buffer[..size].to_vec())).is_err() {
+                    return Ok(());
+                }
+            }
+        });
+        if let Err(e) = result {
+            let _ = sender.blocking_send(Err(e));
+        }
+    }
+
+    // 文件读写及摘要计算在阻塞线程池执行，避免占用异步工作线程
+    async fn blocking<T>(f: impl FnOnce() -> AppResult<T> + Send + 'static) -> AppResult<T>
+    where
+        T: Send + 'static,
+    {
+        tokio::task::spawn_blocking(f)
+            .await
+            .map_err(anyhow::Error::from)?
+    }
+
+    async fn is_attached(partition: &str) -> AppResult<bool> {
+        let partitions = PartitionModel::fetch_all(postgres::conn()).await?;
+        Ok(partitions.iter().any(|p| p.name == partition))
+    }
+
+    async fn load_manifest(dir: &Path, partition: &str) -> AppResult<Option<ArchiveManifest>> {
+        let dir = dir.to_path_buf();
+        let partition = partition.to_string();
+        Self::blocking(move || ArchiveManifest::load(&dir, &partition)).await
+    }
+
+    async fn find_manifest(dir: &Path, partition: &str) -> AppResult<ArchiveManifest> {
+        Self::load_manifest(dir, partition)
+            .await?
+            .ok_or_else(|| Error::Custom(StatusCode::NOT_FOUND, "归档不存在".to_string()))
+    }
+
+    async fn dir() -> AppResult<PathBuf> {
+        let dir = PathBuf::from(
+            std::env::var(ARCHIVE_DIR_ENV).unwrap_or_else(|_| ARCHIVE_DEFAULT_DIR.to_string()),
+        );
+        Self::blocking(move || {
+            fs::create_dir_all(&dir).map_err(anyhow::Error::from)?;
+            Ok(dir)
+        })
+        .await
+    }
+
+    fn partition_name(month: NaiveDate) -> String {
+        format!("account_log_{}", month.format("%Y%m"))
+    }
+
+    fn parse_partition_month(partition: &str) -> Option<NaiveDate> {
+        let month = partition.strip_prefix("account_log_")?;
+        NaiveDate::parse_from_str(&format!("{month}01"), "%Y%m%d").ok()
+    }
+}
@@ -1,31 +1,148 @@
-use crate::model::asset_type::AssetTypeModel;
-use axum_kit::{AppResult, postgres};
-use std::sync::OnceLock;
+use crate::{
+    model::{account::AccountModel, asset_type::AssetTypeModel},
+    request::{AssetTypeActiveRequest, AssetTypeCreateRequest, AssetTypeUpdateRequest},
+};
+use axum::http::StatusCode;
+use axum_kit::{AppResult, error::Error, postgres};
+use sqlx::PgPool;
+use std::sync::{Arc, RwLock};
+use validator::Validate;
 
-static ASSET_TYPE: OnceLock<Vec<AssetTypeModel>> = OnceLock::new();
+static ASSET_TYPE: RwLock<Option<Arc<Vec<AssetTypeModel>>>> = RwLock::new(None);
 
 pub struct AssetTypeService;
 
 impl AssetTypeService {
     pub async fn init() -> AppResult<()> {
-        let asset_types = AssetTypeModel::fetch_all(postgres::conn()).await?;
-        let _ = ASSET_TYPE
-            .set(asset_types)
-            .map_err(|_| "Failed to initialize ASSET_TYPE");
+        Self::reload().await
+    }
+
+    // 从数据库重新加载，替换整个缓存
+    pub async fn reload() -> AppResult<()> {
+        Self::reload_with(postgres::conn()).await
+    }
+
+    pub async fn reload_with(pool: &PgPool) -> AppResult<()> {
+        let asset_types = AssetTypeModel::fetch_all(pool).await?;
+        *ASSET_TYPE.write().unwrap() = Some(Arc::new(asset_types));
         Ok(())
     }
 
-    pub fn list() -> &'static Vec<AssetTypeModel> {
-        ASSET_TYPE.get().expect("ASSET_TYPE is not initialized")
+    // 缓存是否已加载
+    pub fn is_initialized() -> bool {
+        ASSET_TYPE.read().unwrap().is_some()
+    }
+
+    // 缓存未加载时返回503，由后台任务重试加载
+    pub fn list() -> AppResult<Arc<Vec<AssetTypeModel>>> {
+        ASSET_TYPE.read().unwrap().clone().ok_or_else(|| {
+            Error::Custom(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "资产类型缓存未加载".to_string(),
+            )
+        })
     }
 
-    pub fn is_active(id: i32) -> bool {
-        let asset_types = Self::list();
-        asset_types.iter().any(|asset_type| asset_type.id == id)
+    pub fn is_active(id: i32) -> AppResult<bool> {
+        let asset_types = Self::list()?;
+        Ok(asset_types.iter().any(|asset_type| asset_type.id == id))
     }
 
-    pub fn ids() -> Vec<i32> {
-        let asset_types = Self::list();
-        asset_types.iter().map(|asset_type| asset_type.id).collect()
+    pub fn by_id(id: i32) -> AppResult<Option<AssetTypeModel>> {
+        let asset_types = Self::list()?;
+        Ok(asset_types
+            .iter()
+            .find(|&asset_type| asset_type.id == id)
+            .cloned())
+    }
+
+    pub fn ids() -> AppResult<Vec<i32>> {
+        let asset_types = Self::list()?;
+        Ok(asset_types.iter().map(|asset_type| asset_type.id).collect())
+    }
+
+    pub async fn create(
+        asset_type_create_request: &AssetTypeCreateRequest,
+    ) -> AppResult<AssetTypeModel> {
+        asset_type_create_request.validate()?;
+        let pool = postgres::conn();
+        Self::check_name_unique(&asset_type_create_request.name, None).await?;
+        let asset_type = AssetTypeModel::create(
+            pool,
+            &asset_type_create_request.name,
+            &asset_type_create_request.description,
+            asset_type_create_request.is_active,
+        )
+        .await?;
+        Self::reload().await?;
+        Ok(asset_type)
+    }
+
+    pub async fn update(
+        asset_type_update_request: &AssetTypeUpdateRequest,
+    ) -> AppResult<AssetTypeModel> {
+        asset_type_update_request.validate()?;
+        Self::find(asset_type_update_request.id).await?;
+        if let Some(name) = &asset_type_update_request.name {
+            Self::check_name_unique(name, Some(asset_type_update_request.id)).await?;
+        }
+        let asset_type = AssetTypeModel::update(
+            postgres::conn(),
+            asset_type_update_request.id,
+            asset_type_update_request.name.as_deref(),
+            asset_type_update_request.description.as_deref(),
+        )
+        .await?;
+        Self::reload().await?;
+        Ok(asset_type)
+    }
+
+    pub async fn activate(
+        asset_type_active_request: &AssetTypeActiveRequest,
+    ) -> AppResult<AssetTypeModel> {
+        asset_type_active_request.validate()?;
+        Self::find(asset_type_active_request.id).await?;
+        let asset_type =
+            AssetTypeModel::set_active(postgres::conn(), asset_type_active_request.id, true)
+                .await?;
+        Self::reload().await?;
+        Ok(asset_type)
+    }
+
+    // 存在余额不为零的账户时拒绝停用，除非指定`force`
+    pub async fn deactivate(
+        asset_type_active_request: &AssetTypeActiveRequest,
+    ) -> AppResult<AssetTypeModel> {
+        asset_type_active_request.validate()?;
+        let pool = postgres::conn();
+        Self::find(asset_type_active_request.id).await?;
+        if !asset_type_active_request.force
+            && AccountModel::has_nonzero_balance(pool, asset_type_active_request.id).await
+        {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "停用失败，存在余额不为零的账户".to_string(),
+            ));
+        }
+        let asset_type =
+            AssetTypeModel::set_active(pool, asset_type_active_request.id, false).await?;
+        Self::reload().await?;
+        Ok(asset_type)
+    }
+
+    async fn find(id: i32) -> AppResult<AssetTypeModel> {
+        AssetTypeModel::find(postgres::conn(), id)
+            .await?
+            .ok_or_else(|| Error::Custom(StatusCode::NOT_FOUND, "资产类型不存在".to_string()))
+    }
+
+    async fn check_name_unique(name: &str, exclude_id: Option<i32>) -> AppResult<()> {
+        if AssetTypeModel::is_name_exists(postgres::conn(), name, exclude_id).await {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，资产类型名称已存在".to_string(),
+            ));
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,133 @@
+use crate::{
+    constant::{BALANCE_BROADCAST_CAPACITY, BALANCE_CHANGE_CHANNEL, LISTENER_RETRY_INTERVAL},
+    model::{account::AccountModel, client::ClientModel},
+    request::AccountSubscribeRequest,
+};
+use axum::{http::StatusCode, response::sse::Event};
+use axum_kit::{AppResult, error::Error, postgres};
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use std::{
+    convert::Infallible,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::sync::broadcast;
+use tokio_stream::{
+    Stream, StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+use validator::Validate;
+
+static SENDER: OnceLock<broadcast::Sender<BalanceMessage>> = OnceLock::new();
+
+#[derive(Clone)]
+enum BalanceMessage {
+    // 余额变更，`payload`为通知内容原文
+    Changed {
+        user_id: i32,
+        asset_type_id: i32,
+        payload: Arc<str>,
+    },
+    // 监听连接断开期间的通知已丢失，订阅者需重新获取余额
+    Resync,
+}
+
+#[derive(Deserialize)]
+struct BalanceNotification {
+    user_id: i32,
+    asset_type_id: i32,
+}
+
+pub struct BalanceStreamService;
+
+impl BalanceStreamService {
+    fn sender() -> &'static broadcast::Sender<BalanceMessage> {
+        SENDER.get_or_init(|| broadcast::channel(BALANCE_BROADCAST_CAPACITY).0)
+    }
+
+    // 监听余额变更通知并广播给本实例的订阅者
+    pub async fn run_listen_task() {
+        loop {
+            if let Err(e) = Self::listen().await {
+                tracing::error!("监听余额变更失败: {}", e);
+            }
+            let _ = Self::sender().send(BalanceMessage::Resync);
+            tokio::time::sleep(Duration::from_secs(LISTENER_RETRY_INTERVAL)).await;
+        }
+    }
+
+    async fn listen() -> AppResult<()> {
+        let mut listener = PgListener::connect_with(postgres::conn()).await?;
+        listener.listen(BALANCE_CHANGE_CHANNEL).await?;
+        loop {
+            let message = match listener.try_recv().await? {
+                Some(notification) => {
+                    match serde_json::from_str::<BalanceNotification>(notification.payload()) {
+                        Ok(balance) => BalanceMessage::Changed {
+                            user_id: balance.user_id,
+                            asset_type_id: balance.asset_type_id,
+                            payload: notification.payload().into(),
+                        },
+                        Err(e) => {
+                            tracing::error!("无效的余额变更通知: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                // 连接断开后会自动重连，断开期间的通知已丢失
+                None => BalanceMessage::Resync,
+            };
+            // 没有订阅者时发送失败，忽略即可
+            let _ = Self::sender().send(message);
+        }
+    }
+
+    // 订阅用户余额变更
+    // 先推送当前余额（`balance`事件），之后每次余额变更提交后推送最新余额
+    // 通知丢失或订阅者处理过慢时推送`resync`事件，客户端应重新获取余额
+    pub async fn subscribe(
+        client: &ClientModel,
+        account_subscribe_request: &AccountSubscribeRequest,
+    ) -> AppResult<impl Stream<Item = Result<Event, Infallible>> + use<>> {
+        account_subscribe_request.validate()?;
+        let asset_type_ids = match account_subscribe_request.asset_type_id {
+            Some(asset_type_id) if !client.asset_type_ids.contains(&asset_type_id) => {
+                return Err(Error::Custom(
+                    StatusCode::FORBIDDEN,
+                    "订阅失败，无权使用该资产类型".to_string(),
+                ));
+            }
+            Some(asset_type_id) => vec![asset_type_id],
+            None => client.asset_type_ids.clone(),
+        };
+        let user_id = account_subscribe_request.user_id;
+        // 先订阅再读取当前余额，避免遗漏两者之间的变更
+        let receiver = Self::sender().subscribe();
+        let accounts =
+            AccountModel::find_multiple(postgres::conn(), user_id, asset_type_ids.clone()).await?;
+        let snapshot = accounts
+            .iter()
+            .map(|account| {
+                serde_json::to_string(account).map(|payload| Ok(Self::balance(&payload)))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::from)?;
+        let updates = BroadcastStream::new(receiver).filter_map(move |message| match message {
+            Ok(BalanceMessage::Changed {
+                user_id: changed_user_id,
+                asset_type_id,
+                payload,
+            }) => (changed_user_id == user_id && asset_type_ids.contains(&asset_type_id))
+                .then(|| Ok(Self::balance(&payload))),
+            Ok(BalanceMessage::Resync) | Err(BroadcastStreamRecvError::Lagged(_)) => {
+                Some(Ok(Event::default().event("resync").data("")))
+            }
+        });
+        Ok(tokio_stream::iter(snapshot).chain(updates))
+    }
+
+    fn balance(payload: &str) -> Event {
+        Event::default().event("balance").data(payload)
+    }
+}
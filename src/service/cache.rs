@@ -0,0 +1,55 @@
+use super::{action_type::ActionTypeService, asset_type::AssetTypeService};
+use crate::constant::{CACHE_RELOAD_INTERVAL, CONFIG_CHANGE_CHANNEL, LISTENER_RETRY_INTERVAL};
+use axum_kit::{AppResult, postgres};
+use sqlx::postgres::PgListener;
+use std::time::Duration;
+
+pub struct CacheService;
+
+impl CacheService {
+    // 监听`asset_type`、`action_type`变更通知并刷新缓存，同时定时全量刷新兜底
+    pub async fn run_reload_task() {
+        loop {
+            if let Err(e) = Self::listen().await {
+                tracing::error!("监听配置变更失败: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(LISTENER_RETRY_INTERVAL)).await;
+        }
+    }
+
+    async fn listen() -> AppResult<()> {
+        let mut listener = PgListener::connect_with(postgres::conn()).await?;
+        listener.listen(CONFIG_CHANGE_CHANNEL).await?;
+        // 建立监听前的变更不会收到通知，先全量刷新一次
+        Self::reload_all().await;
+        let mut interval = tokio::time::interval(Duration::from_secs(CACHE_RELOAD_INTERVAL));
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                notification = listener.try_recv() => match notification? {
+                    Some(notification) => Self::reload(notification.payload()).await,
+                    // 连接断开后会自动重连，断开期间的通知已丢失
+                    None => Self::reload_all().await,
+                },
+                _ = interval.tick() => Self::reload_all().await,
+            }
+        }
+    }
+
+    async fn reload(table_name: &str) {
+        let result = match table_name {
+            "asset_type" => AssetTypeService::reload().await,
+            "action_type" => ActionTypeService::reload().await,
+            _ => return,
+        };
+        match result {
+            Ok(()) => tracing::debug!("已刷新缓存: {}", table_name),
+            Err(e) => tracing::error!("刷新缓存失败: {}, error={}", table_name, e),
+        }
+    }
+
+    async fn reload_all() {
+        Self::reload("asset_type").await;
+        Self::reload("action_type").await;
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    constant::ENCRYPTED_SECRET_PREFIX,
+    model::client::ClientModel,
+    request::{ClientCreateRequest, ClientSigningRequest, ClientUpdateRequest},
+    response::{ClientCreatedResponse, ClientSigningResponse},
+    utils,
+};
+use axum::http::StatusCode;
+use axum_kit::{AppResult, error::Error, postgres};
+use validator::Validate;
+
+pub struct ClientService;
+
+impl ClientService {
+    // 添加客户端，返回的API密钥仅此一次可见，数据库只保存其摘要
+    pub async fn create(
+        client_create_request: &ClientCreateRequest,
+    ) -> AppResult<ClientCreatedResponse> {
+        client_create_request.validate()?;
+        let pool = postgres::conn();
+        if ClientModel::is_exists(pool, &client_create_request.name).await {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "添加失败，该客户端已存在".to_string(),
+            ));
+        }
+        let api_key = Self::generate_secret();
+        let signing_secret = client_create_request.signing.then(Self::generate_secret);
+        let encrypted_signing_secret = signing_secret
+            .as_deref()
+            .map(utils::encrypt_secret)
+            .transpose()?;
+        let client = ClientModel::create(
+            pool,
+            &client_create_request.name,
+            &utils::sha256_hex(&api_key),
+            &client_create_request.asset_type_ids,
+            &client_create_request.action_type_ids,
+            encrypted_signing_secret.as_deref(),
+        )
+        .await?;
+        Ok(ClientCreatedResponse {
+            client,
+            api_key,
+            signing_secret,
+        })
+    }
+
+    pub async fn update(client_update_request: &ClientUpdateRequest) -> AppResult<ClientModel> {
+        client_update_request.validate()?;
+        ClientModel::update(
+            postgres::conn(),
+            client_update_request.id,
+            client_update_request.asset_type_ids.as_deref(),
+            client_update_request.action_type_ids.as_deref(),
+            client_update_request.is_active,
+        )
+        .await?
+        .ok_or_else(Self::not_found)
+    }
+
+    // 启用或停用请求签名，启用时生成新的签名密钥并仅返回一次
+    pub async fn signing(
+        client_signing_request: &ClientSigningRequest,
+    ) -> AppResult<ClientSigningResponse> {
+        client_signing_request.validate()?;
+        let signing_secret = client_signing_request.enabled.then(Self::generate_secret);
+        let encrypted_signing_secret = signing_secret
+            .as_deref()
+            .map(utils::encrypt_secret)
+            .transpose()?;
+        let client = ClientModel::set_signing_secret(
+            postgres::conn(),
+            client_signing_request.id,
+            encrypted_signing_secret.as_deref(),
+        )
+        .await?
+        .ok_or_else(Self::not_found)?;
+        Ok(ClientSigningResponse {
+            client,
+            signing_secret,
+        })
+    }
+
+    // 加密升级前保存的明文签名密钥
+    pub async fn encrypt_signing_secrets() -> AppResult<usize> {
+        let pool = postgres::conn();
+        let secrets = ClientModel::plaintext_signing_secrets(pool, ENCRYPTED_SECRET_PREFIX).await?;
+        for (id, plaintext) in &secrets {
+            let encrypted = utils::encrypt_secret(plaintext)?;
+            ClientModel::encrypt_signing_secret(pool, *id, plaintext, &encrypted).await?;
+        }
+        Ok(secrets.len())
+    }
+
+    // 按API密钥查找已启用的客户端
+    pub async fn authenticate(api_key: &str) -> AppResult<ClientModel> {
+        if !api_key.is_empty()
+            && let Some(client) =
+                ClientModel::find_by_api_key_hash(postgres::conn(), &utils::sha256_hex(api_key))
+                    .await?
+            && client.is_active
+        {
+            return Ok(client);
+        }
+        Err(Error::Custom(
+            StatusCode::UNAUTHORIZED,
+            "无效的API密钥".to_string(),
+        ))
+    }
+
+    pub fn generate_secret() -> String {
+        hex::encode(rand::random::<[u8; 32]>())
+    }
+
+    fn not_found() -> Error {
+        Error::Custom(StatusCode::NOT_FOUND, "客户端不存在".to_string())
+    }
+}
@@ -0,0 +1,66 @@
+use super::{
+    action_type::ActionTypeService, asset_type::AssetTypeService, partition::PartitionService,
+};
+use crate::{
+    constant::READINESS_DB_TIMEOUT,
+    model::{self, partition::PartitionModel},
+    response::{HealthCheck, ReadinessReport},
+};
+use axum_kit::{AppResult, postgres};
+use std::time::Duration;
+
+pub struct HealthService;
+
+impl HealthService {
+    // 就绪检查：数据库可访问、缓存已加载、当前及下个月分区已创建
+    pub async fn readiness() -> ReadinessReport {
+        let database = Self::with_timeout(model::ping(postgres::conn())).await;
+        let partitions = match &database {
+            Ok(()) => Self::with_timeout(Self::check_partitions())
+                .await
+                .and_then(|result| result),
+            Err(_) => Err("数据库不可访问".to_string()),
+        };
+        let caches = if !AssetTypeService::is_initialized() {
+            Err("资产类型缓存未加载".to_string())
+        } else if !ActionTypeService::is_initialized() {
+            Err("操作类型缓存未加载".to_string())
+        } else {
+            Ok(())
+        };
+        let checks: Vec<HealthCheck> = [
+            ("database", database),
+            ("caches", caches),
+            ("partitions", partitions),
+        ]
+        .into_iter()
+        .map(|(name, result)| HealthCheck {
+            name,
+            ok: result.is_ok(),
+            error: result.err(),
+        })
+        .collect();
+        ReadinessReport {
+            ready: checks.iter().all(|check| check.ok),
+            checks,
+        }
+    }
+
+    async fn check_partitions() -> AppResult<Result<(), String>> {
+        let partitions = PartitionModel::fetch_all(postgres::conn()).await?;
+        let missing = PartitionService::missing(&partitions, 1);
+        if missing.is_empty() {
+            return Ok(Ok(()));
+        }
+        Ok(Err(format!("缺少分区: {}", missing.join(", "))))
+    }
+
+    // 连接池获取连接的超时时间较长，就绪检查需尽快返回
+    async fn with_timeout<T>(future: impl Future<Output = AppResult<T>>) -> Result<T, String> {
+        match tokio::time::timeout(Duration::from_secs(READINESS_DB_TIMEOUT), future).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("数据库访问超时".to_string()),
+        }
+    }
+}
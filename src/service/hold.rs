@@ -0,0 +1,473 @@
+use super::account::AccountService;
+use crate::{
+    constant::{
+        HOLD_AUTHORIZE_ACTION_TYPE, HOLD_CAPTURE_ACTION_TYPE, HOLD_EXPIRY_BATCH_SIZE,
+        HOLD_EXPIRY_INTERVAL, HOLD_RELEASE_ACTION_TYPE,
+    },
+    model::{
+        account_log::AccountLogContext,
+        client::ClientModel,
+        hold::{HoldModel, HoldStatus},
+        outbox::{BalanceChange, EventType},
+    },
+    request::{
+        AccountActionRequest, HoldAuthorizeRequest, HoldCaptureRequest, HoldRequest,
+        HoldVoidRequest,
+    },
+};
+use axum::http::StatusCode;
+use axum_kit::{AppResult, error::Error, postgres};
+use sqlx::{PgPool, types::chrono::Utc};
+use std::time::Duration;
+use validator::Validate;
+
+pub struct HoldService;
+
+impl HoldService {
+    // 预授权：冻结指定金额，直至扣款、撤销或过期
+    pub async fn authorize(
+        client: &ClientModel,
+        hold_authorize_request: &HoldAuthorizeRequest,
+    ) -> AppResult<HoldModel> {
+        Self::authorize_with(postgres::conn(), client, hold_authorize_request).await
+    }
+
+    async fn authorize_with(
+        pool: &PgPool,
+        client: &ClientModel,
+        hold_authorize_request: &HoldAuthorizeRequest,
+    ) -> AppResult<HoldModel> {
+        hold_authorize_request.validate()?;
+        let action_type = AccountService::action_type_by_name(HOLD_AUTHORIZE_ACTION_TYPE)?;
+        let account_action_request = AccountActionRequest {
+            user_id: hold_authorize_request.user_id,
+            asset_type_id: hold_authorize_request.asset_type_id,
+            action_type_id: action_type.id,
+            amount: hold_authorize_request.amount,
+            order_number: hold_authorize_request.order_number.clone(),
+            description: hold_authorize_request.description.clone(),
+        };
+        AccountService::check_permission(client, &account_action_request)?;
+        let mut tx = pool.begin().await?;
+        if HoldModel::is_exists(&mut *tx, &hold_authorize_request.order_number).await {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，存在已处理的订单".to_string(),
+            ));
+        }
+        AccountService::check_before_update(&mut tx, &account_action_request, &action_type).await?;
+        let hold = HoldModel::create(
+            &mut *tx,
+            hold_authorize_request.user_id,
+            hold_authorize_request.asset_type_id,
+            client.id,
+            hold_authorize_request.amount.abs().trunc_with_scale(6),
+            hold_authorize_request.order_number.as_ref(),
+            hold_authorize_request.description.as_ref(),
+            hold_authorize_request.expires_in,
+        )
+        .await?;
+        let account_log = AccountService::update_balance(
+            &mut tx,
+            &account_action_request,
+            &action_type,
+            AccountLogContext {
+                client_id: Some(client.id),
+                ..Default::default()
+            },
+        )
+        .await?;
+        AccountService::commit(
+            tx,
+            EventType::HoldAuthorize,
+            Some(client.id),
+            vec![BalanceChange::new(&account_action_request, &account_log)],
+        )
+        .await?;
+        Ok(hold)
+    }
+
+    // 扣款：从预授权冻结金额中扣除，支持多次部分扣款
+    pub async fn capture(
+        client: &ClientModel,
+        hold_capture_request: &HoldCaptureRequest,
+    ) -> AppResult<HoldModel> {
+        Self::capture_with(postgres::conn(), client, hold_capture_request).await
+    }
+
+    async fn capture_with(
+        pool: &PgPool,
+        client: &ClientModel,
+        hold_capture_request: &HoldCaptureRequest,
+    ) -> AppResult<HoldModel> {
+        hold_capture_request.validate()?;
+        let action_type = AccountService::action_type_by_name(HOLD_CAPTURE_ACTION_TYPE)?;
+        let mut tx = pool.begin().await?;
+        let hold =
+            Self::find_active_for_update(&mut tx, client, hold_capture_request.hold_id).await?;
+        let amount = hold_capture_request.amount.abs().trunc_with_scale(6);
+        if amount > hold.remaining_amount() {
+            return Err(Error::Custom(
+                StatusCode::BAD_REQUEST,
+                "操作失败，扣款金额超出预授权剩余金额".to_string(),
+            ));
+        }
+        let account_action_request = AccountActionRequest {
+            user_id: hold.user_id,
+            asset_type_id: hold.asset_type_id,
+            action_type_id: action_type.id,
+            amount,
+            order_number: hold_capture_request.order_number.clone(),
+            description: hold_capture_request.description.clone(),
+        };
+        AccountService::check_permission(client, &account_action_request)?;
+        AccountService::check_before_update(&mut tx, &account_action_request, &action_type).await?;
+        let account_log = AccountService::update_balance(
+            &mut tx,
+            &account_action_request,
+            &action_type,
+            AccountLogContext {
+                client_id: Some(client.id),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let hold = HoldModel::capture(&mut *tx, hold.id, amount).await?;
+        AccountService::commit(
+            tx,
+            EventType::HoldCapture,
+            Some(client.id),
+            vec![BalanceChange::new(&account_action_request, &account_log)],
+        )
+        .await?;
+        Ok(hold)
+    }
+
+    // 撤销：释放预授权剩余冻结金额
+    pub async fn void(
+        client: &ClientModel,
+        hold_void_request: &HoldVoidRequest,
+    ) -> AppResult<HoldModel> {
+        Self::void_with(postgres::conn(), client, hold_void_request).await
+    }
+
+    async fn void_with(
+        pool: &PgPool,
+        client: &ClientModel,
+        hold_void_request: &HoldVoidRequest,
+    ) -> AppResult<HoldModel> {
+        hold_void_request.validate()?;
+        let mut tx = pool.begin().await?;
+        let hold = Self::find_active_for_update(&mut tx, client, hold_void_request.hold_id).await?;
+        let (hold, changes) = Self::release(
+            &mut tx,
+            hold,
+            HoldStatus::Voided,
+            hold_void_request.description.as_ref(),
+            Some(client),
+        )
+        .await?;
+        AccountService::commit(tx, EventType::HoldVoid, Some(client.id), changes).await?;
+        Ok(hold)
+    }
+
+    pub async fn info(client: &ClientModel, hold_request: &HoldRequest) -> AppResult<HoldModel> {
+        hold_request.validate()?;
+        HoldModel::find(postgres::conn(), hold_request.hold_id)
+            .await?
+            .filter(|hold| hold.client_id == Some(client.id))
+            .ok_or_else(Self::not_found)
+    }
+
+    // 释放所有已过期的预授权，单个预授权失败不影响其余预授权
+    pub async fn release_expired() -> AppResult<usize> {
+        Self::release_expired_with(postgres::conn()).await
+    }
+
+    async fn release_expired_with(pool: &PgPool) -> AppResult<usize> {
+        let ids = HoldModel::expired_ids(pool, HOLD_EXPIRY_BATCH_SIZE).await?;
+        let mut released = 0;
+        for id in ids {
+            match Self::expire(pool, id).await {
+                Ok(true) => released += 1,
+                Ok(false) => {}
+                Err(e) => tracing::error!("释放过期预授权失败: hold_id={}, error={}", id, e),
+            }
+        }
+        Ok(released)
+    }
+
+    // 定时释放过期预授权
+    pub async fn run_expiry_task() {
+        let mut interval = tokio::time::interval(Duration::from_secs(HOLD_EXPIRY_INTERVAL));
+        loop {
+            interval.tick().await;
+            match Self::release_expired().await {
+                Ok(0) => {}
+                Ok(released) => tracing::info!("已释放过期预授权: {}", released),
+                Err(e) => tracing::error!("扫描过期预授权失败: {}", e),
+            }
+        }
+    }
+
+    async fn expire(pool: &PgPool, id: i64) -> AppResult<bool> {
+        let mut tx = pool.begin().await?;
+        // 加锁后再次确认状态，避免与扣款、撤销并发处理
+        let hold = match HoldModel::find_for_update(&mut *tx, id).await? {
+            Some(hold) if hold.status == HoldStatus::Active && hold.expires_at <= Utc::now() => {
+                hold
+            }
+            _ => return Ok(false),
+        };
+        let (_, changes) = Self::release(
+            &mut tx,
+            hold,
+            HoldStatus::Expired,
+            "预授权过期自动释放",
+            None,
+        )
+        .await?;
+        AccountService::commit(tx, EventType::HoldExpire, None, changes).await?;
+        Ok(true)
+    }
+
+    // 释放预授权剩余冻结金额，返回释放后的预授权及余额变更
+    async fn release(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        hold: HoldModel,
+        status: HoldStatus,
+        description: &str,
+        client: Option<&ClientModel>,
+    ) -> AppResult<(HoldModel, Vec<BalanceChange>)> {
+        let amount = hold.remaining_amount();
+        if amount.is_zero() {
+            let hold = HoldModel::release(&mut **tx, hold.id, amount, status).await?;
+            return Ok((hold, Vec::new()));
+        }
+        let action_type = AccountService::action_type_by_name(HOLD_RELEASE_ACTION_TYPE)?;
+        let account_action_request = AccountActionRequest {
+            user_id: hold.user_id,
+            asset_type_id: hold.asset_type_id,
+            action_type_id: action_type.id,
+            amount,
+            order_number: hold.order_number.clone(),
+            description: description.to_string(),
+        };
+        if let Some(client) = client {
+            AccountService::check_permission(client, &account_action_request)?;
+        }
+        // 释放记录沿用预授权订单号，由预授权状态保证只释放一次，不检查订单号是否已处理
+        // 避免同订单号已有解冻记录时预授权始终无法释放
+        AccountService::check_account_state(tx, &account_action_request, &action_type, true)
+            .await?;
+        let account_log = AccountService::update_balance(
+            tx,
+            &account_action_request,
+            &action_type,
+            AccountLogContext {
+                client_id: client.map(|client| client.id),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let hold = HoldModel::release(&mut **tx, hold.id, amount, status).await?;
+        Ok((
+            hold,
+            vec![BalanceChange::new(&account_action_request, &account_log)],
+        ))
+    }
+
+    async fn find_active_for_update(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        client: &ClientModel,
+        hold_id: i64,
+    ) -> AppResult<HoldModel> {
+        // 其他客户端的预授权按不存在处理，避免通过id探测
+        let hold = HoldModel::find_for_update(&mut **tx, hold_id)
+            .await?
+            .filter(|hold| hold.client_id == Some(client.id))
+            .ok_or_else(Self::not_found)?;
+        if hold.status != HoldStatus::Active {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，预授权已结束".to_string(),
+            ));
+        }
+        if hold.expires_at <= Utc::now() {
+            return Err(Error::Custom(
+                StatusCode::CONFLICT,
+                "操作失败，预授权已过期".to_string(),
+            ));
+        }
+        Ok(hold)
+    }
+
+    fn not_found() -> Error {
+        Error::Custom(StatusCode::NOT_FOUND, "预授权不存在".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constant::HOLD_MIN_EXPIRES_IN,
+        model::account::AccountModel,
+        service::{action_type::ActionTypeService, asset_type::AssetTypeService},
+    };
+    use sqlx::types::Decimal;
+
+    const USER_ID: i32 = 1;
+    const ASSET_TYPE_ID: i32 = 1;
+
+    // 订单号至少32位
+    fn order_number(tag: &str) -> String {
+        format!("{tag:-<32}")
+    }
+
+    // 加载缓存，创建客户端及可用余额为100的账户
+    async fn setup(pool: &PgPool) -> ClientModel {
+        AssetTypeService::reload_with(pool).await.unwrap();
+        ActionTypeService::reload_with(pool).await.unwrap();
+        let action_type_ids = [
+            HOLD_AUTHORIZE_ACTION_TYPE,
+            HOLD_CAPTURE_ACTION_TYPE,
+            HOLD_RELEASE_ACTION_TYPE,
+        ]
+        .map(|name| AccountService::action_type_by_name(name).unwrap().id);
+        let client = ClientModel::create(
+            pool,
+            "hold-test",
+            "",
+            &[ASSET_TYPE_ID],
+            &action_type_ids,
+            None,
+        )
+        .await
+        .unwrap();
+        let account = AccountModel::create(pool, USER_ID, ASSET_TYPE_ID)
+            .await
+            .unwrap();
+        sqlx::query!(
+            r#"update account
+                set available_balance = 100,
+                total_income = 100
+            where
+                id = $1"#,
+            account.id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        client
+    }
+
+    async fn authorize(pool: &PgPool, client: &ClientModel, amount: i64) -> HoldModel {
+        let request = HoldAuthorizeRequest {
+            user_id: USER_ID,
+            asset_type_id: ASSET_TYPE_ID,
+            amount: Decimal::from(amount),
+            order_number: order_number("hold-authorize"),
+            description: "预授权".to_string(),
+            expires_in: HOLD_MIN_EXPIRES_IN,
+        };
+        HoldService::authorize_with(pool, client, &request)
+            .await
+            .unwrap()
+    }
+
+    async fn capture(
+        pool: &PgPool,
+        client: &ClientModel,
+        hold_id: i64,
+        amount: i64,
+        tag: &str,
+    ) -> AppResult<HoldModel> {
+        let request = HoldCaptureRequest {
+            hold_id,
+            amount: Decimal::from(amount),
+            order_number: order_number(tag),
+            description: "扣款".to_string(),
+        };
+        HoldService::capture_with(pool, client, &request).await
+    }
+
+    // 可用余额、冻结余额、累计支出
+    async fn balances(pool: &PgPool) -> (Decimal, Decimal, Decimal) {
+        let account = AccountModel::find(pool, USER_ID, ASSET_TYPE_ID)
+            .await
+            .unwrap();
+        (
+            account.available_balance,
+            account.frozen_balance,
+            account.total_expense,
+        )
+    }
+
+    #[sqlx::test]
+    async fn authorize_capture_and_void(pool: PgPool) {
+        let client = setup(&pool).await;
+        let hold = authorize(&pool, &client, 60).await;
+        assert_eq!(hold.status, HoldStatus::Active);
+        assert_eq!(balances(&pool).await, (40.into(), 60.into(), 0.into()));
+
+        let hold = capture(&pool, &client, hold.id, 20, "hold-capture-1")
+            .await
+            .unwrap();
+        assert_eq!(hold.captured_amount, Decimal::from(20));
+        assert_eq!(balances(&pool).await, (40.into(), 40.into(), 20.into()));
+        // 超出剩余金额的扣款被拒绝，余额不变
+        let result = capture(&pool, &client, hold.id, 50, "hold-capture-2").await;
+        assert!(matches!(
+            result,
+            Err(Error::Custom(StatusCode::BAD_REQUEST, _))
+        ));
+
+        let request = HoldVoidRequest {
+            hold_id: hold.id,
+            description: "撤销".to_string(),
+        };
+        let hold = HoldService::void_with(&pool, &client, &request)
+            .await
+            .unwrap();
+        assert_eq!(hold.status, HoldStatus::Voided);
+        assert_eq!(hold.released_amount, Decimal::from(40));
+        assert_eq!(balances(&pool).await, (80.into(), 0.into(), 20.into()));
+        // 已撤销的预授权不能再扣款
+        let result = capture(&pool, &client, hold.id, 10, "hold-capture-3").await;
+        assert!(matches!(
+            result,
+            Err(Error::Custom(StatusCode::CONFLICT, _))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn expired_hold_is_released(pool: PgPool) {
+        let client = setup(&pool).await;
+        let hold = authorize(&pool, &client, 30).await;
+        sqlx::query!(
+            r#"update hold
+                set expires_at = now() - interval '1 second'
+            where
+                id = $1"#,
+            hold.id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // 过期后不能再扣款
+        let result = capture(&pool, &client, hold.id, 10, "hold-capture").await;
+        assert!(matches!(
+            result,
+            Err(Error::Custom(StatusCode::CONFLICT, _))
+        ));
+
+        assert_eq!(HoldService::release_expired_with(&pool).await.unwrap(), 1);
+        let hold = HoldModel::find(&pool, hold.id).await.unwrap().unwrap();
+        assert_eq!(hold.status, HoldStatus::Expired);
+        assert_eq!(hold.released_amount, Decimal::from(30));
+        assert_eq!(balances(&pool).await, (100.into(), 0.into(), 0.into()));
+        // 已释放的预授权不会重复释放
+        assert_eq!(HoldService::release_expired_with(&pool).await.unwrap(), 0);
+    }
+}
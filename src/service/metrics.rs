@@ -0,0 +1,208 @@
+use super::{action_type::ActionTypeService, asset_type::AssetTypeService};
+use crate::model::outbox::BalanceChange;
+use axum_kit::{AppResult, postgres};
+use prometheus::{
+    CounterVec, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder, core::Collector,
+};
+use rust_decimal::prelude::ToPrimitive;
+use std::{sync::LazyLock, time::Duration};
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+// 账户操作被拒绝的原因
+#[derive(Clone, Copy)]
+pub enum RejectionReason {
+    InsufficientBalance,
+    DuplicateOrder,
+    InactiveAccount,
+}
+
+impl RejectionReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::InsufficientBalance => "insufficient_balance",
+            Self::DuplicateOrder => "duplicate_order",
+            Self::InactiveAccount => "inactive_account",
+        }
+    }
+}
+
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    actions: IntCounterVec,
+    action_amount: CounterVec,
+    rejections: IntCounterVec,
+    transaction_retries: IntCounterVec,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        fn register<T: Collector + Clone + 'static>(registry: &Registry, collector: T) -> T {
+            registry
+                .register(Box::new(collector.clone()))
+                .expect("failed to register metric");
+            collector
+        }
+        let metrics = Self {
+            http_requests: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("stardust_http_requests_total", "请求数量"),
+                    &["method", "route", "status"],
+                )
+                .unwrap(),
+            ),
+            http_request_duration: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new("stardust_http_request_duration_seconds", "请求耗时"),
+                    &["method", "route"],
+                )
+                .unwrap(),
+            ),
+            actions: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("stardust_account_actions_total", "账户操作数量"),
+                    &["action_type", "asset_type"],
+                )
+                .unwrap(),
+            ),
+            action_amount: register(
+                &registry,
+                CounterVec::new(
+                    Opts::new("stardust_account_action_amount_total", "账户操作金额"),
+                    &["action_type", "asset_type"],
+                )
+                .unwrap(),
+            ),
+            rejections: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("stardust_account_rejections_total", "账户操作被拒绝数量"),
+                    &["reason"],
+                )
+                .unwrap(),
+            ),
+            transaction_retries: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("stardust_transaction_retries_total", "事务重试次数"),
+                    &["reason"],
+                )
+                .unwrap(),
+            ),
+            db_pool_connections: register(
+                &registry,
+                IntGaugeVec::new(
+                    Opts::new("stardust_db_pool_connections", "数据库连接池连接数"),
+                    &["state"],
+                )
+                .unwrap(),
+            ),
+            db_pool_max_connections: register(
+                &registry,
+                IntGauge::new("stardust_db_pool_max_connections", "数据库连接池最大连接数")
+                    .unwrap(),
+            ),
+            registry,
+        };
+        // 预先初始化固定取值的标签，未发生时也输出0
+        for reason in [
+            RejectionReason::InsufficientBalance,
+            RejectionReason::DuplicateOrder,
+            RejectionReason::InactiveAccount,
+        ] {
+            metrics.rejections.with_label_values(&[reason.as_str()]);
+        }
+        for reason in ["deadlock", "serialization_failure"] {
+            metrics.transaction_retries.with_label_values(&[reason]);
+        }
+        metrics
+    }
+}
+
+pub struct MetricsService;
+
+impl MetricsService {
+    // 按路由模板记录请求数量和耗时，避免路径参数导致标签过多
+    pub fn observe_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+        METRICS
+            .http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        METRICS
+            .http_request_duration
+            .with_label_values(&[method, route])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    // 记录已提交的账户操作，按类型名称标记，缓存中找不到时使用id
+    pub fn record_changes(changes: &[BalanceChange]) {
+        for change in changes {
+            let labels = [
+                ActionTypeService::list_all()
+                    .ok()
+                    .and_then(|action_types| {
+                        action_types
+                            .iter()
+                            .find(|action_type| action_type.id == change.action_type_id)
+                            .map(|action_type| action_type.name.clone())
+                    })
+                    .unwrap_or_else(|| change.action_type_id.to_string()),
+                AssetTypeService::by_id(change.asset_type_id)
+                    .ok()
+                    .flatten()
+                    .map(|asset_type| asset_type.name)
+                    .unwrap_or_else(|| change.asset_type_id.to_string()),
+            ];
+            METRICS.actions.with_label_values(&labels).inc();
+            METRICS
+                .action_amount
+                .with_label_values(&labels)
+                .inc_by(change.amount.to_f64().unwrap_or_default());
+        }
+    }
+
+    pub fn record_rejection(reason: RejectionReason) {
+        METRICS
+            .rejections
+            .with_label_values(&[reason.as_str()])
+            .inc();
+    }
+
+    pub fn record_transaction_retry(reason: &str) {
+        METRICS
+            .transaction_retries
+            .with_label_values(&[reason])
+            .inc();
+    }
+
+    // Prometheus 文本格式
+    pub fn render() -> AppResult<String> {
+        let pool = postgres::conn();
+        let idle = pool.num_idle() as i64;
+        METRICS
+            .db_pool_connections
+            .with_label_values(&["idle"])
+            .set(idle);
+        METRICS
+            .db_pool_connections
+            .with_label_values(&["active"])
+            .set(pool.size() as i64 - idle);
+        METRICS
+            .db_pool_max_connections
+            .set(pool.options().get_max_connections() as i64);
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&METRICS.registry.gather(), &mut buffer)
+            .map_err(anyhow::Error::from)?;
+        Ok(String::from_utf8(buffer).map_err(anyhow::Error::from)?)
+    }
+}
@@ -1,3 +1,15 @@
 pub mod account;
 pub mod action_type;
+pub mod archive;
 pub mod asset_type;
+pub mod balance_stream;
+pub mod cache;
+pub mod client;
+pub mod health;
+pub mod hold;
+pub mod metrics;
+pub mod outbox;
+pub mod partition;
+pub mod reconciliation;
+pub mod signature;
+pub mod webhook;
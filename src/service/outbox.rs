@@ -0,0 +1,89 @@
+use crate::{
+    constant::{
+        OUTBOX_BATCH_SIZE, OUTBOX_CLEANUP_INTERVAL, OUTBOX_ENDPOINT_ENV, OUTBOX_INTERVAL,
+        OUTBOX_LOCK_NAME, OUTBOX_RETENTION_DAYS,
+    },
+    model::{self, outbox::OutboxEventModel},
+    utils,
+};
+use axum_kit::{AppResult, postgres};
+use std::time::Duration;
+
+pub struct OutboxService;
+
+impl OutboxService {
+    // 按发布序号发布一批事件，返回发布成功的数量
+    // 发布失败时停止本批次，下次从失败的事件重新开始，保证顺序及至少一次送达
+    // 持有会话级咨询锁，只有一个实例分配序号及发布，每个事件发布成功后单独标记，发布期间不持有事务
+    pub async fn dispatch(endpoint: &str) -> AppResult<usize> {
+        let mut conn = postgres::conn().acquire().await?;
+        if !model::try_advisory_lock(&mut *conn, OUTBOX_LOCK_NAME).await? {
+            return Ok(0);
+        }
+        let result = Self::publish(endpoint).await;
+        // 释放失败时关闭连接，避免锁随连接留在连接池中
+        if let Err(e) = model::advisory_unlock(&mut *conn, OUTBOX_LOCK_NAME).await {
+            let _ = conn.close().await;
+            return Err(e);
+        }
+        result
+    }
+
+    async fn publish(endpoint: &str) -> AppResult<usize> {
+        OutboxEventModel::assign_publish_seq(postgres::conn()).await?;
+        let events =
+            OutboxEventModel::fetch_unpublished(postgres::conn(), OUTBOX_BATCH_SIZE).await?;
+        let mut published = 0;
+        for event in &events {
+            Self::send(endpoint, event).await?;
+            OutboxEventModel::mark_published(postgres::conn(), event.id).await?;
+            published += 1;
+        }
+        Ok(published)
+    }
+
+    async fn send(endpoint: &str, event: &OutboxEventModel) -> AppResult<()> {
+        utils::http_client()
+            .post(endpoint)
+            .json(event)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    // 定时发布事件，批次已满时立即继续发布剩余事件
+    pub async fn run_dispatch_task() {
+        let Ok(endpoint) = std::env::var(OUTBOX_ENDPOINT_ENV) else {
+            tracing::warn!("未设置{}，余额变更事件不会发布", OUTBOX_ENDPOINT_ENV);
+            return;
+        };
+        let mut interval = tokio::time::interval(Duration::from_secs(OUTBOX_INTERVAL));
+        loop {
+            interval.tick().await;
+            loop {
+                match Self::dispatch(&endpoint).await {
+                    Ok(published) if published as i64 == OUTBOX_BATCH_SIZE => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("发布余额变更事件失败: {}", e),
+                }
+                break;
+            }
+        }
+    }
+
+    // 定时清理超出保留期的已发布事件
+    pub async fn run_cleanup_task() {
+        let mut interval = tokio::time::interval(Duration::from_secs(OUTBOX_CLEANUP_INTERVAL));
+        loop {
+            interval.tick().await;
+            match OutboxEventModel::delete_published(postgres::conn(), OUTBOX_RETENTION_DAYS).await
+            {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("已清理已发布事件: {}", deleted),
+                Err(e) => tracing::error!("清理已发布事件失败: {}", e),
+            }
+        }
+    }
+}
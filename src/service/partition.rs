@@ -0,0 +1,58 @@
+use crate::{
+    constant::{PARTITION_LOCK_NAME, PARTITION_MAINTENANCE_INTERVAL, PARTITION_MONTHS_AHEAD},
+    model::{self, partition::PartitionModel},
+    response::PartitionStatus,
+};
+use axum_kit::{AppResult, postgres};
+use chrono::{Datelike, Months, Utc};
+use std::time::Duration;
+
+pub struct PartitionService;
+
+impl PartitionService {
+    // 确保当前月份及未来数月的分区存在，返回本实例是否执行了维护
+    pub async fn maintain() -> AppResult<bool> {
+        let mut tx = postgres::conn().begin().await?;
+        if !model::try_advisory_xact_lock(&mut *tx, PARTITION_LOCK_NAME).await? {
+            return Ok(false);
+        }
+        PartitionModel::ensure(&mut tx, PARTITION_MONTHS_AHEAD).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    // 定时维护分区，避免跨月时因缺少分区导致写入失败
+    pub async fn run_maintenance_task() {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(PARTITION_MAINTENANCE_INTERVAL));
+        loop {
+            interval.tick().await;
+            match Self::maintain().await {
+                Ok(true) => {}
+                Ok(false) => tracing::info!("分区维护由其他实例执行，本次跳过"),
+                Err(e) => tracing::error!("分区维护失败: {}", e),
+            }
+        }
+    }
+
+    // 分区状态，`missing`为当前月份及未来数月中缺少的分区
+    pub async fn status() -> AppResult<PartitionStatus> {
+        let partitions = PartitionModel::fetch_all(postgres::conn()).await?;
+        let missing = Self::missing(&partitions, PARTITION_MONTHS_AHEAD as u32);
+        Ok(PartitionStatus {
+            months_ahead: PARTITION_MONTHS_AHEAD,
+            partitions,
+            missing,
+        })
+    }
+
+    // 当前月份及未来`months_ahead`个月中缺少的分区名称
+    pub fn missing(partitions: &[PartitionModel], months_ahead: u32) -> Vec<String> {
+        let now = Utc::now();
+        (0..=months_ahead)
+            .filter_map(|i| now.checked_add_months(Months::new(i)))
+            .map(|month| format!("account_log_{:04}{:02}", month.year(), month.month()))
+            .filter(|name| !partitions.iter().any(|partition| &partition.name == name))
+            .collect()
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    constant::{RECONCILIATION_BATCH_SIZE, RECONCILIATION_INTERVAL, RECONCILIATION_LOCK_NAME},
+    model::{self, reconciliation::ReconciliationModel},
+    request::ReconciliationRequest,
+    response::{DriftSource, ReconciliationDrift, ReconciliationReport},
+};
+use axum_kit::{AppResult, postgres};
+use sqlx::types::Decimal;
+use std::time::Duration;
+use validator::Validate;
+
+pub struct ReconciliationService;
+
+impl ReconciliationService {
+    // 对账：账户当前余额应与最后一条操作记录的变更后余额一致，也应与全部操作记录的变动合计一致
+    // 前者发现最后一次操作之后的直接修改，后者发现第一条在线记录之后任意时间点的直接修改
+    pub async fn run(
+        reconciliation_request: &ReconciliationRequest,
+    ) -> AppResult<ReconciliationReport> {
+        reconciliation_request.validate()?;
+        let mut report = ReconciliationReport {
+            checked_accounts: 0,
+            drifts: Vec::new(),
+        };
+        let mut after_account_id = 0;
+        loop {
+            let rows = ReconciliationModel::fetch_batch(
+                postgres::conn(),
+                after_account_id,
+                reconciliation_request.user_id,
+                reconciliation_request.asset_type_id,
+                RECONCILIATION_BATCH_SIZE,
+            )
+            .await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            after_account_id = last.account_id;
+            report.checked_accounts += rows.len();
+            for row in &rows {
+                Self::check(row, &mut report.drifts);
+            }
+        }
+        for drift in &report.drifts {
+            tracing::warn!(
+                "账户余额与操作记录不一致: account_id={}, source={:?}, field={}, expected={}, actual={}, delta={}",
+                drift.account_id,
+                drift.source,
+                drift.field,
+                drift.expected,
+                drift.actual,
+                drift.delta
+            );
+        }
+        Ok(report)
+    }
+
+    // 定时对账
+    pub async fn run_task() {
+        let mut interval = tokio::time::interval(Duration::from_secs(RECONCILIATION_INTERVAL));
+        loop {
+            interval.tick().await;
+            match Self::run_scheduled().await {
+                Ok(None) => {}
+                Ok(Some(report)) => tracing::info!(
+                    "对账完成: checked_accounts={}, drifts={}",
+                    report.checked_accounts,
+                    report.drifts.len()
+                ),
+                Err(e) => tracing::error!("对账失败: {}", e),
+            }
+        }
+    }
+
+    // 全量对账，未取得咨询锁时说明其他实例正在执行，返回空
+    async fn run_scheduled() -> AppResult<Option<ReconciliationReport>> {
+        // 持有咨询锁直至本次对账结束，多实例部署时只有一个实例执行
+        let mut lock = postgres::conn().begin().await?;
+        if !model::try_advisory_xact_lock(&mut *lock, RECONCILIATION_LOCK_NAME).await? {
+            return Ok(None);
+        }
+        let request = ReconciliationRequest {
+            user_id: None,
+            asset_type_id: None,
+        };
+        let report = Self::run(&request).await?;
+        lock.commit().await?;
+        Ok(Some(report))
+    }
+
+    fn check(row: &ReconciliationModel, drifts: &mut Vec<ReconciliationDrift>) {
+        let fields = [
+            (
+                "available_balance",
+                row.available_balance,
+                row.available_balance_after,
+                row.sum_available_balance,
+            ),
+            (
+                "frozen_balance",
+                row.frozen_balance,
+                row.frozen_balance_after,
+                row.sum_frozen_balance,
+            ),
+            (
+                "total_income",
+                row.total_income,
+                row.total_income_after,
+                row.sum_total_income,
+            ),
+            (
+                "total_expense",
+                row.total_expense,
+                row.total_expense_after,
+                row.sum_total_expense,
+            ),
+        ];
+        for (field, actual, last_after, sum) in fields {
+            // 没有操作记录的账户余额应为零
+            let sources = [
+                (DriftSource::LastLog, last_after.unwrap_or(Decimal::ZERO)),
+                (DriftSource::LogSum, sum),
+            ];
+            for (source, expected) in sources {
+                if actual != expected {
+                    drifts.push(ReconciliationDrift {
+                        account_id: row.account_id,
+                        user_id: row.user_id,
+                        asset_type_id: row.asset_type_id,
+                        source,
+                        field,
+                        expected,
+                        actual,
+                        delta: actual - expected,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    constant::{
+        NONCE_CLEANUP_INTERVAL, SIGNATURE_HEADER, SIGNATURE_MAX_AGE, SIGNATURE_NONCE_HEADER,
+        SIGNATURE_TIMESTAMP_HEADER,
+    },
+    model::{client::ClientModel, request_nonce::RequestNonceModel},
+    utils,
+};
+use axum::http::{HeaderMap, StatusCode};
+use axum_kit::{AppResult, error::Error, postgres};
+use sqlx::types::chrono::Utc;
+use std::time::Duration;
+
+pub struct SignatureService;
+
+impl SignatureService {
+    // 校验请求签名
+    // 签名内容为`method\npath\ntimestamp\nnonce\nbody`（`path`含查询参数），密钥为客户端签名密钥
+    // 签名通过后才记录随机数，避免伪造请求占用合法随机数
+    pub async fn verify(
+        client: &ClientModel,
+        method: &str,
+        path: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> AppResult<()> {
+        let Some(signing_secret) = client.signing_secret.as_deref() else {
+            return Ok(());
+        };
+        let signing_secret = utils::decrypt_secret(signing_secret)?;
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+        };
+        let timestamp = header(SIGNATURE_TIMESTAMP_HEADER);
+        let nonce = header(SIGNATURE_NONCE_HEADER);
+        let signature = header(SIGNATURE_HEADER);
+        let Ok(timestamp_secs) = timestamp.parse::<i64>() else {
+            return Err(Self::unauthorized("无效的请求时间戳"));
+        };
+        if (Utc::now().timestamp() - timestamp_secs).abs() > SIGNATURE_MAX_AGE {
+            return Err(Self::unauthorized("请求已过期"));
+        }
+        if nonce.is_empty() || nonce.len() > 64 {
+            return Err(Self::unauthorized("无效的请求随机数"));
+        }
+        let data = [
+            format!("{method}\n{path}\n{timestamp}\n{nonce}\n").as_bytes(),
+            body,
+        ]
+        .concat();
+        if !utils::verify_hmac_sha256_hex(signing_secret, data, signature)? {
+            return Err(Self::unauthorized("无效的请求签名"));
+        }
+        if !RequestNonceModel::create(postgres::conn(), client.id, nonce).await? {
+            return Err(Self::unauthorized("重复的请求"));
+        }
+        Ok(())
+    }
+
+    // 定时清理超出有效期的随机数
+    // 保留两倍有效期，覆盖客户端时钟超前的情况
+    pub async fn run_nonce_cleanup_task() {
+        let mut interval = tokio::time::interval(Duration::from_secs(NONCE_CLEANUP_INTERVAL));
+        loop {
+            interval.tick().await;
+            match RequestNonceModel::delete_expired(postgres::conn(), SIGNATURE_MAX_AGE * 2).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("已清理过期请求随机数: {}", deleted),
+                Err(e) => tracing::error!("清理过期请求随机数失败: {}", e),
+            }
+        }
+    }
+
+    fn unauthorized(message: &str) -> Error {
+        Error::Custom(StatusCode::UNAUTHORIZED, message.to_string())
+    }
+}
@@ -0,0 +1,287 @@
+use super::client::ClientService;
+use crate::{
+    constant::{
+        ENCRYPTED_SECRET_PREFIX, WEBHOOK_BATCH_SIZE, WEBHOOK_DELIVERY_LIMIT, WEBHOOK_ID_HEADER,
+        WEBHOOK_INTERVAL, WEBHOOK_LEASE, WEBHOOK_MAX_ATTEMPTS, WEBHOOK_RETRY_BASE,
+        WEBHOOK_RETRY_MAX, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER,
+    },
+    model::{
+        client::ClientModel,
+        webhook::{WebhookDeliveryModel, WebhookDeliveryTask, WebhookSubscriptionModel},
+    },
+    request::{
+        WebhookCreateRequest, WebhookDeliveriesRequest, WebhookRedeliverRequest,
+        WebhookUpdateRequest,
+    },
+    response::WebhookCreatedResponse,
+    utils,
+};
+use axum::http::StatusCode;
+use axum_kit::{AppResult, error::Error, postgres};
+use sqlx::{PgPool, types::chrono::Utc};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use validator::Validate;
+
+pub struct WebhookService;
+
+impl WebhookService {
+    // 添加回调订阅，返回的签名密钥仅此一次可见
+    pub async fn create(
+        webhook_create_request: &WebhookCreateRequest,
+    ) -> AppResult<WebhookCreatedResponse> {
+        webhook_create_request.validate()?;
+        let pool = postgres::conn();
+        if !ClientModel::is_exists_by_id(pool, webhook_create_request.client_id).await {
+            return Err(Error::Custom(
+                StatusCode::NOT_FOUND,
+                "客户端不存在".to_string(),
+            ));
+        }
+        let secret = ClientService::generate_secret();
+        let subscription = WebhookSubscriptionModel::create(
+            pool,
+            webhook_create_request.client_id,
+            &webhook_create_request.url,
+            &utils::encrypt_secret(&secret)?,
+            &webhook_create_request.asset_type_ids,
+            &webhook_create_request.action_type_ids,
+        )
+        .await?;
+        Ok(WebhookCreatedResponse {
+            subscription,
+            secret,
+        })
+    }
+
+    // 加密升级前保存的明文签名密钥
+    pub async fn encrypt_secrets() -> AppResult<usize> {
+        let pool = postgres::conn();
+        let secrets =
+            WebhookSubscriptionModel::plaintext_secrets(pool, ENCRYPTED_SECRET_PREFIX).await?;
+        for (id, plaintext) in &secrets {
+            let encrypted = utils::encrypt_secret(plaintext)?;
+            WebhookSubscriptionModel::encrypt_secret(pool, *id, plaintext, &encrypted).await?;
+        }
+        Ok(secrets.len())
+    }
+
+    pub async fn update(
+        webhook_update_request: &WebhookUpdateRequest,
+    ) -> AppResult<WebhookSubscriptionModel> {
+        webhook_update_request.validate()?;
+        WebhookSubscriptionModel::update(
+            postgres::conn(),
+            webhook_update_request.id,
+            webhook_update_request.url.as_deref(),
+            webhook_update_request.asset_type_ids.as_deref(),
+            webhook_update_request.action_type_ids.as_deref(),
+            webhook_update_request.is_active,
+        )
+        .await?
+        .ok_or_else(|| Error::Custom(StatusCode::NOT_FOUND, "回调订阅不存在".to_string()))
+    }
+
+    // 最近的投递记录
+    pub async fn deliveries(
+        webhook_deliveries_request: &WebhookDeliveriesRequest,
+    ) -> AppResult<Vec<WebhookDeliveryModel>> {
+        webhook_deliveries_request.validate()?;
+        WebhookDeliveryModel::fetch(
+            postgres::conn(),
+            webhook_deliveries_request.subscription_id,
+            webhook_deliveries_request.status,
+            WEBHOOK_DELIVERY_LIMIT,
+        )
+        .await
+    }
+
+    // 重新投递，通常用于处理`DEAD`状态的回调
+    pub async fn redeliver(
+        webhook_redeliver_request: &WebhookRedeliverRequest,
+    ) -> AppResult<WebhookDeliveryModel> {
+        webhook_redeliver_request.validate()?;
+        WebhookDeliveryModel::redeliver(postgres::conn(), webhook_redeliver_request.id)
+            .await?
+            .ok_or_else(|| Error::Custom(StatusCode::NOT_FOUND, "投递记录不存在".to_string()))
+    }
+
+    // 并发投递一批到期的回调，返回处理的数量
+    // 失败时按指数退避安排重试，超过最大尝试次数后标记为`DEAD`
+    pub async fn deliver() -> AppResult<usize> {
+        Self::deliver_with(postgres::conn()).await
+    }
+
+    // 领取、发送、标记结果分开进行，发送期间不持有事务及行锁，每条结果单独更新
+    async fn deliver_with(pool: &PgPool) -> AppResult<usize> {
+        let tasks =
+            WebhookDeliveryModel::claim_due(pool, WEBHOOK_BATCH_SIZE, WEBHOOK_LEASE).await?;
+        let mut join_set = JoinSet::new();
+        for task in tasks {
+            join_set.spawn(async move {
+                let result = Self::send(&task).await;
+                (task, result)
+            });
+        }
+        let mut processed = 0;
+        while let Some(joined) = join_set.join_next().await {
+            let (task, result) = joined.map_err(anyhow::Error::from)?;
+            match result {
+                Ok(()) => WebhookDeliveryModel::mark_succeeded(pool, task.id).await?,
+                Err(e) => {
+                    let retry_after = Self::retry_after(task.attempts + 1);
+                    WebhookDeliveryModel::mark_failed(pool, task.id, &e, retry_after).await?;
+                }
+            }
+            processed += 1;
+        }
+        Ok(processed)
+    }
+
+    // 请求体为`{"id", "event_type", "created_at", "data"}`
+    // 签名为以订阅密钥对`timestamp\nbody`计算的 HMAC-SHA256，十六进制编码，密钥仅在签名时解密
+    async fn send(task: &WebhookDeliveryTask) -> Result<(), String> {
+        let body = serde_json::to_vec(&serde_json::json!({
+            "id": task.id,
+            "event_type": task.event_type,
+            "created_at": task.created_at,
+            "data": task.payload,
+        }))
+        .map_err(|e| e.to_string())?;
+        let timestamp = Utc::now().timestamp().to_string();
+        let secret = utils::decrypt_secret(&task.secret).map_err(|e| e.to_string())?;
+        let signature =
+            utils::hmac_sha256_hex(&secret, [timestamp.as_bytes(), b"\n", &body].concat())
+                .map_err(|e| e.to_string())?;
+        utils::http_client()
+            .post(&task.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, task.id)
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp)
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // 第n次失败后的重试等待时间（秒），达到最大尝试次数时不再重试
+    fn retry_after(attempts: i32) -> Option<i64> {
+        if attempts >= WEBHOOK_MAX_ATTEMPTS {
+            return None;
+        }
+        let delay = WEBHOOK_RETRY_BASE.saturating_mul(1 << (attempts - 1).min(30));
+        Some(delay.min(WEBHOOK_RETRY_MAX))
+    }
+
+    // 定时投递回调，批次已满时立即继续投递
+    pub async fn run_delivery_task() {
+        let mut interval = tokio::time::interval(Duration::from_secs(WEBHOOK_INTERVAL));
+        loop {
+            interval.tick().await;
+            loop {
+                match Self::deliver().await {
+                    Ok(processed) if processed as i64 == WEBHOOK_BATCH_SIZE => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("投递回调失败: {}", e),
+                }
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::webhook::WebhookDeliveryStatus;
+    use axum::{Router, body::Bytes, http::HeaderMap, routing::post};
+
+    const SECRET: &str = "webhook-test-secret";
+
+    // 测试用的主密钥，只设置一次
+    fn init_secret_key() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            // 仅在首次读取环境变量前设置
+            unsafe { std::env::set_var(crate::constant::SECRET_KEY_ENV, "11".repeat(32)) };
+        });
+    }
+
+    // 本地回调接收服务，签名正确时返回`status`，否则返回401
+    async fn serve(status: StatusCode) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Router::new().route(
+            "/",
+            post(move |headers: HeaderMap, body: Bytes| async move {
+                let header = |name: &str| headers.get(name).unwrap().to_str().unwrap().to_string();
+                let data = [header(WEBHOOK_TIMESTAMP_HEADER).as_bytes(), b"\n", &body].concat();
+                match utils::verify_hmac_sha256_hex(SECRET, data, &header(WEBHOOK_SIGNATURE_HEADER))
+                {
+                    Ok(true) => status,
+                    _ => StatusCode::UNAUTHORIZED,
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}/")
+    }
+
+    // 创建订阅及一条已尝试`attempts`次的投递记录，返回订阅id
+    async fn create_delivery(pool: &PgPool, url: &str, attempts: i32) -> i32 {
+        init_secret_key();
+        let client = ClientModel::create(pool, "webhook-test", "", &[], &[], None)
+            .await
+            .unwrap();
+        let secret = utils::encrypt_secret(SECRET).unwrap();
+        let subscription =
+            WebhookSubscriptionModel::create(pool, client.id, url, &secret, &[], &[])
+                .await
+                .unwrap();
+        sqlx::query!(
+            r#"insert into webhook_delivery(subscription_id, event_type, payload, attempts)
+                values ($1, 'account.actions', '{}', $2)"#,
+            subscription.id,
+            attempts
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        subscription.id
+    }
+
+    async fn fetch_delivery(pool: &PgPool, subscription_id: i32) -> WebhookDeliveryModel {
+        WebhookDeliveryModel::fetch(pool, subscription_id, None, 1)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn deliver_marks_succeeded(pool: PgPool) {
+        let url = serve(StatusCode::OK).await;
+        let subscription_id = create_delivery(&pool, &url, 0).await;
+        assert_eq!(WebhookService::deliver_with(&pool).await.unwrap(), 1);
+        let delivery = fetch_delivery(&pool, subscription_id).await;
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Succeeded);
+        assert_eq!(delivery.attempts, 1);
+        assert!(delivery.delivered_at.is_some());
+    }
+
+    #[sqlx::test]
+    async fn deliver_marks_dead_after_max_attempts(pool: PgPool) {
+        let url = serve(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let subscription_id = create_delivery(&pool, &url, WEBHOOK_MAX_ATTEMPTS - 1).await;
+        assert_eq!(WebhookService::deliver_with(&pool).await.unwrap(), 1);
+        let delivery = fetch_delivery(&pool, subscription_id).await;
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Dead);
+        assert_eq!(delivery.attempts, WEBHOOK_MAX_ATTEMPTS);
+        assert!(delivery.last_error.contains("500"));
+        // 已标记为`DEAD`的记录不再投递
+        assert_eq!(WebhookService::deliver_with(&pool).await.unwrap(), 0);
+    }
+}
@@ -1,6 +1,15 @@
+use crate::constant::{ENCRYPTED_SECRET_PREFIX, HTTP_TIMEOUT, SECRET_KEY_ENV};
+use aes_gcm::{Aes256Gcm, Nonce, aead::Aead};
 use anyhow::{Result, anyhow};
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
 use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
 use chrono_tz::Tz;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::{fs::File, io, path::Path, sync::OnceLock, time};
 
 /// 起止时间边界
 pub enum DayBoundary {
@@ -30,3 +39,95 @@ pub fn parse_day_boundary(date_str: &str, tz: Tz, boundary: DayBoundary) -> Resu
 
     Ok(dt.with_timezone(&Utc))
 }
+
+/// 计算 SHA-256 摘要并以十六进制字符串返回
+pub fn sha256_hex(data: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// 计算 HMAC-SHA256 并以十六进制字符串返回
+pub fn hmac_sha256_hex(key: impl AsRef<[u8]>, data: impl AsRef<[u8]>) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_ref())?;
+    mac.update(data.as_ref());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 以常量时间校验十六进制编码的 HMAC-SHA256 签名
+pub fn verify_hmac_sha256_hex(
+    key: impl AsRef<[u8]>,
+    data: impl AsRef<[u8]>,
+    signature: &str,
+) -> Result<bool> {
+    let Ok(signature) = hex::decode(signature) else {
+        return Ok(false);
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_ref())?;
+    mac.update(data.as_ref());
+    Ok(mac.verify_slice(&signature).is_ok())
+}
+
+/// 使用`STARDUST_SECRET_KEY`以 AES-256-GCM 加密密钥，返回带前缀的 base64 密文
+pub fn encrypt_secret(plaintext: &str) -> Result<String> {
+    let nonce = rand::random::<[u8; 12]>();
+    let ciphertext = secret_cipher()?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+        .map_err(|_| anyhow!("加密密钥失败"))?;
+    Ok(format!(
+        "{ENCRYPTED_SECRET_PREFIX}{}",
+        STANDARD.encode([nonce.as_slice(), &ciphertext].concat())
+    ))
+}
+
+/// 解密`encrypt_secret`生成的密文
+pub fn decrypt_secret(encrypted: &str) -> Result<String> {
+    let data = encrypted
+        .strip_prefix(ENCRYPTED_SECRET_PREFIX)
+        .and_then(|encoded| STANDARD.decode(encoded).ok())
+        .filter(|data| data.len() > 12)
+        .ok_or_else(|| anyhow!("无效的加密密钥"))?;
+    let (nonce, ciphertext) = data.split_at(12);
+    let plaintext = secret_cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("解密密钥失败"))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+fn secret_cipher() -> Result<Aes256Gcm> {
+    let key = std::env::var(SECRET_KEY_ENV).map_err(|_| anyhow!("未设置{}", SECRET_KEY_ENV))?;
+    let key = hex::decode(key.trim())?;
+    <Aes256Gcm as aes_gcm::KeyInit>::new_from_slice(&key)
+        .map_err(|_| anyhow!("{}必须为32字节", SECRET_KEY_ENV))
+}
+
+/// 共享的 HTTP 客户端，用于发布事件和投递回调
+pub fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(time::Duration::from_secs(HTTP_TIMEOUT))
+            .build()
+            .expect("failed to build http client")
+    })
+}
+
+/// 把分页位置`id`编码为不透明游标
+pub fn encode_cursor(id: i64) -> String {
+    URL_SAFE_NO_PAD.encode(id.to_string())
+}
+
+/// 解析分页游标，兼容旧版`created_at:id`格式的游标
+pub fn decode_cursor(cursor: &str) -> Result<i64> {
+    let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor)?)?;
+    let id = match decoded.split_once(':') {
+        Some((_, id)) => id,
+        None => &decoded,
+    };
+    id.parse().map_err(|_| anyhow!("游标{}格式无效", cursor))
+}
+
+/// 计算文件的 SHA-256 摘要并以十六进制字符串返回
+pub fn sha256_file(path: impl AsRef<Path>) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}